@@ -0,0 +1,60 @@
+//! Meri hitrost leksikalne analize na ustvarjenem programu velikosti nekaj megabajtov.
+//!
+//! Zagon: <code>cargo bench --bench lexanal</code>
+
+extern crate proteus_compiler;
+
+use std::time::{Duration, Instant};
+use proteus_compiler::lexanal::LexicalAnalyzer;
+
+// priblizna velikost ustvarjenega programa v bajtih
+const SOURCE_SIZE : usize = 8 * 1024 * 1024;
+// stevilo ponovitev meritve; izpise se najhitrejsa
+const RUNS : usize = 5;
+
+/** Ustvari program, ki vsebuje vse vrste osnovnih simbolov. */
+fn generate_source(size : usize) -> String
+{
+    let mut source = String::with_capacity(size + 512);
+    let mut i = 0;
+    while source.len() < size
+    {
+        source.push_str(&format!("# funkcija stevilka {}\n", i));
+        source.push_str(&format!("fun fib{0}(n:int, eps:real):int = {{if n <= 1 & eps >= 0.5e-3 then {{r = n}} else {{r = fib{0}(n - 1, eps) + fib{0}(n - 2, eps * 2.0)}}}}, r\n", i));
+        source.push_str("    where var r:int;;\n");
+        source.push_str(&format!("typ zapis{0} : rec(ime:string, vrednosti:arr[{0}] *int);\n", i));
+        source.push_str(&format!("var niz{0} : string = \"niz stevilka {0}\";\n", i));
+        source.push_str(&format!("{{while !(a{0}.vrednosti[i] <> 42) | b == c : {{i = i % 7 / 3}}}}\n", i));
+        i += 1;
+    }
+    source
+}
+
+fn main()
+{
+    let source = generate_source(SOURCE_SIZE);
+    let megabytes = source.len() as f64 / (1024.0 * 1024.0);
+
+    let mut best : Option<Duration> = None;
+    let mut symbol_count = 0;
+    for _ in 0..RUNS
+    {
+        let start = Instant::now();
+        let mut lexical_analyser = LexicalAnalyzer::new_from_str(String::from("bench.proteus"), &source);
+        symbol_count = 0;
+        while lexical_analyser.get_next_symbol().expect("lexical analysis failed").is_some()
+        {
+            symbol_count += 1;
+        }
+        let elapsed = start.elapsed();
+        if best.map_or(true, |best| elapsed < best)
+        {
+            best = Some(elapsed);
+        }
+    }
+
+    let best = best.unwrap();
+    let seconds = best.as_secs() as f64 + f64::from(best.subsec_nanos()) / 1e9;
+    println!("lexanal: {:.1} MB, {} symbols, best of {} runs: {:.3} s ({:.1} MB/s, {:.2} M symbols/s)",
+             megabytes, symbol_count, RUNS, seconds, megabytes / seconds, symbol_count as f64 / seconds / 1e6);
+}
@@ -3,6 +3,7 @@ use abstree::abs_expr::{AbsExprName,AbsExpr};
 use abstree::positioner::Positioner;
 use abstree::abs_position::AbsPosition;
 use abstree::abs_type::{AbsType,AbsTypeName};
+use interner::Name;
 use lexanal::position::Position;
 use lexanal::symbol::Symbol;
 use abstree::visitor::Visitor;
@@ -31,6 +32,19 @@ impl AbsDecls {
         self.abs_position.set_min(self.decls[0].get_position_ref().unwrap());
         self.abs_position.set_max(self.decls[self.decls.len() - 1].get_position_ref().unwrap());
     }
+    /** Izracuna polozaj le iz deklaracij v podani datoteki; uvozene deklaracije iz drugih datotek se ne upostevajo.  */
+    pub fn calculate_abs_position_in_file(&mut self, filename : Name)
+    {
+        for decl in &self.decls
+        {
+            let position = decl.get_position_ref().unwrap();
+            if position.get_filename_id() == filename
+            {
+                self.abs_position.set_min(position);
+                self.abs_position.set_max(position);
+            }
+        }
+    }
 }
 
 impl AbsTree for AbsDecls 
@@ -61,14 +75,16 @@ pub struct AbsVarDecl
     //variable name
     pub var_name : AbsExprName,
     //variable type
-    pub var_type : Box<AbsType>
+    pub var_type : Box<AbsType>,
+    //documentation comment
+    pub doc : Option<String>,
 }
 
 impl AbsVarDecl
 {
     pub fn new(var_name: AbsExprName, var_type : Box<AbsType>) -> AbsVarDecl
     {
-       let mut abs_var_decl = AbsVarDecl{var_name,var_type, abs_position : AbsPosition::new()};
+       let mut abs_var_decl = AbsVarDecl{var_name,var_type, doc : None, abs_position : AbsPosition::new()};
        abs_var_decl.calculate_abs_position();
        abs_var_decl
     }
@@ -113,13 +129,15 @@ pub struct AbsFunDecl
     pub return_type : Box<AbsType>,
     //function body
     pub exprs : Box<AbsExpr>,
+    //documentation comment
+    pub doc : Option<String>,
 }
 
 impl AbsFunDecl
 {
     pub fn new(name : AbsExprName, params : AbsDecls, return_type : Box<AbsType>, exprs : Box<AbsExpr> ) -> AbsFunDecl
     {
-        let mut fun_decl = AbsFunDecl{name,params,return_type,exprs, abs_position : AbsPosition::new()};
+        let mut fun_decl = AbsFunDecl{name,params,return_type,exprs, doc : None, abs_position : AbsPosition::new()};
         fun_decl.calculate_abs_position();
         fun_decl
     }
@@ -160,13 +178,15 @@ pub struct AbsTypeDecl
     pub type_name : AbsTypeName,
     //source type
     pub source_type : Box<AbsType>,
+    //documentation comment
+    pub doc : Option<String>,
 }
 
 impl AbsTypeDecl
 {
     pub fn new(type_name : AbsTypeName, source_type : Box<AbsType>) -> AbsTypeDecl
     {
-       let mut abs_type_decl = AbsTypeDecl{type_name,source_type,abs_position : AbsPosition::new()};
+       let mut abs_type_decl = AbsTypeDecl{type_name,source_type, doc : None, abs_position : AbsPosition::new()};
        abs_type_decl.calculate_abs_position();
        abs_type_decl
     }
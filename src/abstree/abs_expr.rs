@@ -6,7 +6,7 @@ use abstree::visitor::Visitor;
 use lexanal::position::Position;
 use lexanal::symbol::Symbol;
 use lexanal::symbol::Token;
-use report;
+use report::{Diagnostic, ExitCode};
 
 
 pub trait AbsExpr : AbsTree {}
@@ -77,15 +77,10 @@ impl AbsAtomExpr
         token == Token::BOOLCONST || token == Token::INTCONST  || token == Token::REALCONST 
         || token == Token::STRINGCONST
     }
-    pub fn new(expr : Symbol) -> AbsAtomExpr
+    /** Ustvari konstanto; ce simbol ni konstanta, vrne napako z izhodno kodo <code>AbstractSyntaxTreeInvalidExpression</code>.  */
+    pub fn new(expr : Symbol) -> Result<AbsAtomExpr, Box<Diagnostic>>
     {
-        if !Self::is_const_type(expr.get_token())
-        {
-            report::error("Internal error in AbsAtomExpr: expression is not constant!", report::ExitCode::AbstractSyntaxTreeInvalidExpression);
-        }
-        let mut abs_atom_expr = AbsAtomExpr{expr : Some(expr), abs_position : AbsPosition::new()};
-        abs_atom_expr.calculate_abs_position();
-        abs_atom_expr
+        Self::new_with_option(Some(expr))
     }
 
     pub fn calculate_abs_position(&mut self)
@@ -97,22 +92,20 @@ impl AbsAtomExpr
         }
     }
 
-    pub fn new_with_option(expr : Option<Symbol>) -> AbsAtomExpr
+    /** Ustvari konstanto ali prazen izraz <code>{}</code>, ce simbola ni (obvestilo je v skatli, da ostane rezultat majhen).  */
+    pub fn new_with_option(expr : Option<Symbol>) -> Result<AbsAtomExpr, Box<Diagnostic>>
     {
-        match expr 
+        if let Some(ref e) = expr
         {
-            None => {},
-            Some(ref e) => 
+            if !Self::is_const_type(e.get_token())
             {
-                if !Self::is_const_type(e.get_token())
-                {
-                    report::error("Internal error in AbsAtomExpr: expression is not constant!", report::ExitCode::AbstractSyntaxTreeInvalidExpression);
-                }
+                return Err(Box::new(Diagnostic::error(&format!("Internal error in AbsAtomExpr: {} is not a constant", e.get_token()), Some(e.get_ref_position()),
+                                             ExitCode::AbstractSyntaxTreeInvalidExpression)));
             }
         }
         let mut abs_atom_expr = AbsAtomExpr{expr, abs_position : AbsPosition::new()};
         abs_atom_expr.calculate_abs_position();
-        abs_atom_expr
+        Ok(abs_atom_expr)
     }
     
 }
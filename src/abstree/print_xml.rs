@@ -8,6 +8,7 @@ use abstree::abs_type::*;
 use abstree::visitor::Visitor;
 use abstree::positioner::Positioner;
 use lexanal::position::Position;
+use lexanal::symbol::str_to_xml;
 use std::io::Write;
 
 pub  struct AbsTreeXmlPrinter 
@@ -23,6 +24,16 @@ impl AbsTreeXmlPrinter
     }
 }
 
+// atribut z dokumentacijskim komentarjem deklaracije
+fn doc_attribute(doc : &Option<String>) -> String
+{
+    match *doc
+    {
+        Some(ref doc) => format!(" doc=\"{}\"", str_to_xml(doc)),
+        None => String::new(),
+    }
+}
+
 impl Visitor for AbsTreeXmlPrinter 
 {
     fn visit_abs_arr_type(&mut self,  acceptor : &AbsArrType)
@@ -129,7 +140,7 @@ impl Visitor for AbsTreeXmlPrinter
     }
     fn visit_abs_fun_decl(&mut self, acceptor : &AbsFunDecl)
     {
-        writeln!(self.xml, "<absnode node=\"FunDecl\">").unwrap();
+        writeln!(self.xml, "<absnode node=\"FunDecl\"{}>", doc_attribute(&acceptor.doc)).unwrap();
         acceptor.get_position_ref().map(|position| position.to_xml(&mut self.xml));
         acceptor.name.accept(self);
         acceptor.params.accept(self);
@@ -162,7 +173,7 @@ impl Visitor for AbsTreeXmlPrinter
     }
     fn visit_abs_type_decl(&mut self, acceptor : &AbsTypeDecl)
     {
-        writeln!(self.xml, "<absnode node=\"TypeDecl\">").unwrap();
+        writeln!(self.xml, "<absnode node=\"TypeDecl\"{}>", doc_attribute(&acceptor.doc)).unwrap();
         acceptor.get_position_ref().map(|position| position.to_xml(&mut self.xml));
         acceptor.type_name.accept(self);
         acceptor.source_type.accept(self);
@@ -191,7 +202,7 @@ impl Visitor for AbsTreeXmlPrinter
     }
     fn visit_abs_var_decl(&mut self, acceptor : &AbsVarDecl)
     {
-        writeln!(self.xml, "<absnode node=\"VarDecl\">").unwrap();
+        writeln!(self.xml, "<absnode node=\"VarDecl\"{}>", doc_attribute(&acceptor.doc)).unwrap();
         acceptor.get_position_ref().map(|position| position.to_xml(&mut self.xml));
         acceptor.var_name.accept(self);
         acceptor.var_type.accept(self);
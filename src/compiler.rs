@@ -0,0 +1,238 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use abstree::AbsTree;
+use abstree::abs_decl::AbsDecls;
+use abstree::abs_expr::AbsExpr;
+use abstree::abs_type::AbsType;
+use lexanal::{LexicalAnalyzer, DEFAULT_TAB_WIDTH};
+use lexanal::lex_error::LexError;
+use lexanal::symbol::Symbol;
+use report::Diagnostic;
+use synanal::{SyntaxAnalyzer, DEFAULT_MAX_NESTING_DEPTH};
+use synanal::trace::ParseTracer;
+use report::ExitCode;
+
+/** Faza, do katere (vkljucno) tece prevajanje. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Phase
+{
+    Lexanal,
+    Synanal,
+    Abstree,
+}
+
+impl Phase
+{
+    /** Vrne ime faze, ki je hkrati ime izhodne XML datoteke. */
+    pub fn get_name(&self) -> &'static str
+    {
+        match *self
+        {
+            Phase::Lexanal => "lexanal",
+            Phase::Synanal => "synanal",
+            Phase::Abstree => "abstree",
+        }
+    }
+}
+
+impl FromStr for Phase
+{
+    type Err = String;
+
+    fn from_str(phase : &str) -> Result<Phase, String>
+    {
+        match phase
+        {
+            "lexanal" => Ok(Phase::Lexanal),
+            "synanal" => Ok(Phase::Synanal),
+            "abstree" => Ok(Phase::Abstree),
+            _ => Err(format!("Unknown phase '{}'", phase)),
+        }
+    }
+}
+
+impl fmt::Display for Phase
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+/** Nastavitve prevajanja.  */
+#[derive(Debug, Clone)]
+pub struct CompileOptions
+{
+    // zadnja faza, ki se izvede
+    pub phase : Phase,
+    // sirina tabulatorja za stolpce v polozajih
+    pub tab_width : u64,
+    // najvecja globina gnezdenja izrazov in tipov (skupaj; glej SyntaxAnalyzer::set_max_nesting_depth)
+    pub max_nesting_depth : usize,
+    // direktoriji, v katerih se iscejo uvozene datoteke
+    pub import_path : Vec<PathBuf>,
+}
+
+impl Default for CompileOptions
+{
+    fn default() -> CompileOptions
+    {
+        CompileOptions { phase : Phase::Abstree, tab_width : DEFAULT_TAB_WIDTH, max_nesting_depth : DEFAULT_MAX_NESTING_DEPTH,
+                         import_path : Vec::new() }
+    }
+}
+
+/** Rezultat prevajanja: zaporedje osnovnih simbolov, abstraktno sintaksno drevo in obvestila vseh faz.
+ *
+ * Globina gnezdenja je omejena (<code>CompileOptions::max_nesting_depth</code>), dolzina verig operatorjev pa ne:
+ * izpis in sproscanje drevesa sta rekurzivna, zato drevo za npr. <code>a+a+...+a</code> z desettisoci operandov
+ * potrebuje nit z vecjim skladom (prevajalnik tece v niti s 256 MB sklada).
+ */
+pub struct CompilationResult
+{
+    pub symbols : Vec<Symbol>,
+    // None, ce sintaksna analiza ni bila izvedena, se ni uspesno koncala ali je program prazen
+    pub abstree : Option<Box<AbsTree>>,
+    pub diagnostics : Vec<Diagnostic>,
+}
+
+impl CompilationResult
+{
+    pub fn has_errors(&self) -> bool
+    {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.is_error())
+    }
+
+    /** Vrne izhodno kodo prve zabelezene napake.  */
+    pub fn get_exit_code(&self) -> Option<ExitCode>
+    {
+        self.diagnostics.iter().filter_map(|diagnostic| diagnostic.exit_code).next()
+    }
+}
+
+/** Prevede program do (vkljucno) izbrane faze.
+* @param program_name ime datoteke z izvorno kodo programa.
+* @param options nastavitve prevajanja.
+* @return Rezultat prevajanja ali opis napake, ce datoteke ni mogoce prebrati.
+*/
+pub fn compile(program_name : &str, options : &CompileOptions) -> Result<CompilationResult, String>
+{
+    let lexical_analyser = LexicalAnalyzer::new(program_name.to_string())?;
+    compile_with(lexical_analyser, options, None)
+}
+
+/** Prevede program do (vkljucno) izbrane faze in med sintaksno analizo obvesca podani <code>tracer</code>
+* (glej <code>SyntaxAnalyzer::new_with_tracer</code>); v fazi lexanal se <code>tracer</code> ne uporabi.
+* @param program_name ime datoteke z izvorno kodo programa.
+* @param options nastavitve prevajanja.
+* @param tracer opazovalec sintaksne analize.
+*/
+pub fn compile_with_tracer(program_name : &str, options : &CompileOptions, tracer : Box<ParseTracer>) -> Result<CompilationResult, String>
+{
+    let lexical_analyser = LexicalAnalyzer::new(program_name.to_string())?;
+    compile_with(lexical_analyser, options, Some(tracer))
+}
+
+/** Prevede program, podan kot niz, do (vkljucno) izbrane faze.
+* @param program_name ime, ki se uporabi kot ime datoteke v polozajih in obvestilih.
+* @param source izvorna koda programa.
+* @param options nastavitve prevajanja.
+*/
+pub fn compile_str(program_name : &str, source : &str, options : &CompileOptions) -> Result<CompilationResult, String>
+{
+    let lexical_analyser = LexicalAnalyzer::new_from_str(program_name.to_string(), source);
+    compile_with(lexical_analyser, options, None)
+}
+
+/** Ime, ki se pri razclenjevanju delov programa uporabi kot ime datoteke v polozajih in obvestilih. */
+pub const FRAGMENT_NAME : &str = "<fragment>";
+
+/** Razcleni en izraz, podan kot niz (npr. <code>a[i].x + 1</code>).
+* @param source izvorna koda izraza; za izrazom so dovoljeni le presledki in komentarji.
+* @return Abstraktno sintaksno drevo izraza ali vsa obvestila, ce je bila zabelezena kaksna napaka.
+*/
+pub fn parse_expression_str(source : &str) -> Result<Box<AbsExpr>, Vec<Diagnostic>>
+{
+    parse_fragment_str(source, |syntax_analyzer| syntax_analyzer.parse_expression_fragment())
+}
+
+/** Razcleni opis tipa, podan kot niz (npr. <code>arr[10] *rec(x:int)</code>; glej <code>parse_expression_str</code>).  */
+pub fn parse_type_str(source : &str) -> Result<Box<AbsType>, Vec<Diagnostic>>
+{
+    parse_fragment_str(source, |syntax_analyzer| syntax_analyzer.parse_type_fragment())
+}
+
+/** Razcleni seznam deklaracij, podan kot niz (npr. <code>var i:int; typ t:arr[5] int;</code>; glej <code>parse_expression_str</code>).  */
+pub fn parse_declarations_str(source : &str) -> Result<AbsDecls, Vec<Diagnostic>>
+{
+    parse_fragment_str(source, |syntax_analyzer| syntax_analyzer.parse_declarations_fragment())
+}
+
+fn parse_fragment_str<T, F>(source : &str, parse : F) -> Result<T, Vec<Diagnostic>>
+    where F : FnOnce(&mut SyntaxAnalyzer) -> Result<Option<T>, io::Error>
+{
+    let lexical_analyser = LexicalAnalyzer::new_from_str(FRAGMENT_NAME.to_string(), source);
+    let diagnostics = lexical_analyser.get_diagnostics();
+    let mut syntax_analyzer = SyntaxAnalyzer::new(lexical_analyser);
+    // niz je ze v pomnilniku, zato branje ne more spodleteti
+    let fragment = parse(&mut syntax_analyzer).expect("reading from a string cannot fail");
+    let diagnostics = diagnostics.get_diagnostics();
+    match fragment
+    {
+        Some(fragment) if !diagnostics.iter().any(|diagnostic| diagnostic.is_error()) => Ok(fragment),
+        _ => Err(diagnostics),
+    }
+}
+
+fn compile_with(mut lexical_analyser : LexicalAnalyzer, options : &CompileOptions, tracer : Option<Box<ParseTracer>>) -> Result<CompilationResult, String>
+{
+    let diagnostics = lexical_analyser.get_diagnostics();
+    lexical_analyser.set_tab_width(options.tab_width);
+    lexical_analyser.record_symbols();
+
+    let abstree = match options.phase
+    {
+        Phase::Lexanal =>
+        {
+            drain(&mut lexical_analyser).map_err(|ioe| ioe.to_string())?;
+            None
+        },
+        Phase::Synanal | Phase::Abstree =>
+        {
+            let mut syntax_analyzer = match tracer
+            {
+                Some(tracer) => SyntaxAnalyzer::new_with_tracer(lexical_analyser, tracer),
+                None => SyntaxAnalyzer::new(lexical_analyser),
+            };
+            syntax_analyzer.set_max_nesting_depth(options.max_nesting_depth);
+            syntax_analyzer.set_import_path(options.import_path.clone());
+            let abstree = syntax_analyzer.parse().map_err(|ioe| ioe.to_string())?;
+            lexical_analyser = syntax_analyzer.into_lexical_analyser();
+            // po sintaksni napaki ostanek datoteke se ni prebran
+            drain(&mut lexical_analyser).map_err(|ioe| ioe.to_string())?;
+            abstree
+        },
+    };
+
+    Ok(CompilationResult
+    {
+        symbols : lexical_analyser.take_recorded_symbols(),
+        abstree,
+        diagnostics : diagnostics.get_diagnostics(),
+    })
+}
+
+// prebere vse preostale simbole do konca datoteke
+fn drain(lexical_analyser : &mut LexicalAnalyzer) -> Result<(), io::Error>
+{
+    for symbol in lexical_analyser
+    {
+        if let Err(LexError::IoError(ioe)) = symbol
+        {
+            return Err(ioe);
+        }
+    }
+    Ok(())
+}
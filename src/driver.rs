@@ -0,0 +1,308 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::thread;
+use compiler::{CompilationResult, CompileOptions, Phase};
+use lexanal::{self, DEFAULT_TAB_WIDTH};
+use lexanal::dump::DumpFormat;
+use synanal::{self, DEFAULT_MAX_NESTING_DEPTH};
+use synanal::trace::TraceFormat;
+use report::snippet::{self, SourceCache};
+
+/** Izhodni status: prevajanje se je uspesno koncalo. */
+pub const EXIT_SUCCESS : i32 = 0;
+/** Izhodni status: napacni argumenti ukazne vrstice. */
+pub const EXIT_USAGE_ERROR : i32 = 2;
+/** Izhodni status: vhodne ali izhodne datoteke ni mogoce odpreti. */
+pub const EXIT_IO_ERROR : i32 = 3;
+/** Izhodni status: prevajalnik se je zaradi notranje napake nepricakovano ustavil. */
+pub const EXIT_INTERNAL_ERROR : i32 = 101;
+
+// velikost sklada niti, v kateri tece prevajanje; izpis in sproscanje globokih dreves (npr. a+a+...+a) sta rekurzivna
+const COMPILER_STACK_SIZE : usize = 256 << 20;
+
+/** Kolicina sporocil, ki jih prevajalnik izpise med prevajanjem. */
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub enum Verbosity
+{
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+/** Nastavitve prevajalnika, prebrane iz ukazne vrstice. */
+#[derive(Debug)]
+pub struct Options
+{
+    pub program_name : String,
+    pub phase : Phase,
+    pub output_dir : PathBuf,
+    pub verbosity : Verbosity,
+    // sirina tabulatorja za stolpce v polozajih
+    pub tab_width : u64,
+    // oblika izpisa osnovnih simbolov v fazi lexanal
+    pub format : DumpFormat,
+    // oblika zapisa drevesa izpeljave ali None, ce se drevo ne zapise
+    pub trace : Option<TraceFormat>,
+    // najvecja globina gnezdenja izrazov in tipov
+    pub max_nesting_depth : usize,
+    // direktoriji, v katerih se iscejo uvozene datoteke
+    pub import_path : Vec<PathBuf>,
+    // direktorij s predlogami .xsl, na katere se sklicujejo izhodne datoteke XML, ali None brez sklicev
+    pub xsl_dir : Option<PathBuf>,
+}
+
+impl Options
+{
+    /** Prebere nastavitve iz argumentov ukazne vrstice (brez imena programa).
+    * @param args Argumenti ukazne vrstice.
+    * @return Nastavitve ali opis napake v argumentih.
+    */
+    pub fn from_args(args : Vec<String>) -> Result<Options, String>
+    {
+        let mut program_name : Option<String> = None;
+        let mut phase = Phase::Abstree;
+        let mut output_dir = PathBuf::from(".");
+        let mut verbosity = Verbosity::Normal;
+        let mut tab_width = DEFAULT_TAB_WIDTH;
+        let mut format = DumpFormat::Xml;
+        let mut max_nesting_depth = DEFAULT_MAX_NESTING_DEPTH;
+        let mut import_path = Vec::new();
+        // None, ce oblika ni podana; privzeto se drevo izpeljave zapise le v fazi synanal
+        let mut trace : Option<Option<TraceFormat>> = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next()
+        {
+            match arg.as_str()
+            {
+                "-p" | "--phase" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    phase = value.parse()?;
+                },
+                "-o" | "--output-dir" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    output_dir = PathBuf::from(value);
+                },
+                "--tab-width" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    tab_width = match value.parse()
+                    {
+                        Ok(tab_width) if tab_width > 0 => tab_width,
+                        _ => return Err(format!("Invalid tab width '{}'", value)),
+                    };
+                },
+                "-I" | "--import-path" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    import_path.push(PathBuf::from(value));
+                },
+                "--max-nesting" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    max_nesting_depth = match value.parse()
+                    {
+                        Ok(max_nesting_depth) if max_nesting_depth > 0 => max_nesting_depth,
+                        _ => return Err(format!("Invalid nesting depth '{}'", value)),
+                    };
+                },
+                "-f" | "--format" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    format = value.parse()?;
+                },
+                "-t" | "--trace" =>
+                {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    trace = match value.as_str()
+                    {
+                        "none" => Some(None),
+                        value => Some(Some(value.parse()?)),
+                    };
+                },
+                "-q" | "--quiet" => verbosity = Verbosity::Quiet,
+                "-v" | "--verbose" => verbosity = Verbosity::Verbose,
+                _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+                _ =>
+                {
+                    if program_name.is_some()
+                    {
+                        return Err(format!("Unexpected argument '{}'", arg));
+                    }
+                    program_name = Some(arg);
+                },
+            }
+        }
+
+        if format != DumpFormat::Xml && phase != Phase::Lexanal
+        {
+            return Err(format!("Format '{}' is only available for phase lexanal", format));
+        }
+        let trace = match trace
+        {
+            Some(Some(trace)) if phase == Phase::Lexanal =>
+                return Err(format!("Trace '{}' is only available for phases synanal and abstree", trace)),
+            Some(trace) => trace,
+            None if phase == Phase::Synanal => Some(TraceFormat::Xml),
+            None => None,
+        };
+
+        // spremenljivka PROTEUSXSL ima prednost pred direktorijem xsl v trenutnem direktoriju
+        let xsl_dir = env::var_os("PROTEUSXSL").map(PathBuf::from).or_else(|| env::current_dir().ok().map(|dir| dir.join("xsl")));
+
+        match program_name
+        {
+            Some(program_name) => Ok(Options { program_name, phase, output_dir, verbosity, tab_width, format, trace, max_nesting_depth, import_path,
+                                               xsl_dir }),
+            None => Err(String::from("Missing source file")),
+        }
+    }
+
+    /** Vrne pot do izhodne datoteke zadnje faze (npr. <code>lexanal.jsonl</code> pri obliki JSON). */
+    pub fn get_output_path(&self) -> PathBuf
+    {
+        let extension = match self.phase
+        {
+            Phase::Synanal => self.trace.map_or("xml", |trace| trace.get_extension()),
+            _ => self.format.get_extension(),
+        };
+        self.output_dir.join(format!("{}.{}", self.phase, extension))
+    }
+
+    /** Vrne pot do datoteke z drevesom izpeljave (npr. <code>synanal.txt</code>) ali None, ce se drevo ne zapise. */
+    pub fn get_trace_path(&self) -> Option<PathBuf>
+    {
+        self.trace.map(|trace| self.output_dir.join(format!("{}.{}", Phase::Synanal, trace.get_extension())))
+    }
+
+    /** Vrne direktorij s predlogami <code>.xsl</code> ali None, ce izhodne datoteke XML nanje ne kazejo. */
+    pub fn get_xsl_dir(&self) -> Option<&Path>
+    {
+        self.xsl_dir.as_deref()
+    }
+
+    pub fn is_quiet(&self) -> bool
+    {
+        self.verbosity == Verbosity::Quiet
+    }
+
+    pub fn is_verbose(&self) -> bool
+    {
+        self.verbosity == Verbosity::Verbose
+    }
+}
+
+impl From<&Options> for CompileOptions
+{
+    fn from(options : &Options) -> CompileOptions
+    {
+        CompileOptions { phase : options.phase, tab_width : options.tab_width, max_nesting_depth : options.max_nesting_depth,
+                         import_path : options.import_path.clone() }
+    }
+}
+
+/** Vrne navodila za uporabo prevajalnika. */
+pub fn usage(executable : &str) -> String
+{
+    format!("Usage: {} <file> [options]\n\
+             Options:\n\
+             \x20 -p, --phase <phase>      last compiler phase: lexanal, synanal, abstree (default: abstree)\n\
+             \x20 -o, --output-dir <dir>   directory for the phase output (default: .)\n\
+             \x20     --tab-width <n>      columns between tab stops in positions (default: 4)\n\
+             \x20     --max-nesting <n>    deepest allowed nesting of expressions and types (default: 100)\n\
+             \x20 -I, --import-path <dir>  directory searched for imported files (may be repeated)\n\
+             \x20 -f, --format <format>    token dump format for phase lexanal: xml, json, text (default: xml)\n\
+             \x20 -t, --trace <format>     parse tree trace written to synanal.<ext>: xml, text, json, none\n\
+             \x20                          (default: xml for phase synanal, none otherwise)\n\
+             \x20 -q, --quiet              print errors only\n\
+             \x20 -v, --verbose            print progress of every phase\n\
+             \x20 -h, --help               print this help", executable)
+}
+
+/** Prebere argumente ukazne vrstice, pozene prevajalnik in vrne izhodni status procesa. */
+pub fn main() -> i32
+{
+    let mut args = env::args();
+    let executable = args.next().unwrap_or_else(|| String::from("proteus_compiler"));
+    let args : Vec<String> = args.collect();
+
+    if args.iter().any(|arg| arg == "-h" || arg == "--help")
+    {
+        println!("{}", usage(&executable));
+        return EXIT_SUCCESS;
+    }
+
+    match Options::from_args(args)
+    {
+        Ok(options) => run_with_stack(options),
+        Err(e) =>
+        {
+            eprintln!(":-( {}", e);
+            eprintln!("{}", usage(&executable));
+            EXIT_USAGE_ERROR
+        },
+    }
+}
+
+/** Pozene prevajanje v niti z dovolj velikim skladom za globoka drevesa in vrne izhodni status procesa. */
+pub fn run_with_stack(options : Options) -> i32
+{
+    let compiler = thread::Builder::new().name(String::from("compiler")).stack_size(COMPILER_STACK_SIZE)
+                                         .spawn(move || run(&options));
+    match compiler
+    {
+        // sporocilo o panicnem koncu niti je ze izpisano
+        Ok(compiler) => compiler.join().unwrap_or(EXIT_INTERNAL_ERROR),
+        Err(e) =>
+        {
+            eprintln!(":-( Cannot start the compiler thread: {}", e);
+            EXIT_INTERNAL_ERROR
+        },
+    }
+}
+
+/** Pozene prevajanje do izbrane faze in vrne izhodni status procesa. */
+pub fn run(options : &Options) -> i32
+{
+    if options.is_verbose()
+    {
+        eprintln!("Compiling {} up to phase {}", options.program_name, options.phase);
+    }
+
+    let status = match options.phase
+    {
+        Phase::Lexanal => lexanal::run::run(options),
+        Phase::Synanal | Phase::Abstree => synanal::run::run(options),
+    };
+
+    if status == EXIT_SUCCESS && !options.is_quiet()
+    {
+        // v fazi synanal je izhod le drevo izpeljave
+        let output_path = if options.phase == Phase::Synanal { None } else { Some(options.get_output_path()) };
+        for path in output_path.into_iter().chain(options.get_trace_path())
+        {
+            eprintln!("{}: wrote {}", options.phase, path.display());
+        }
+    }
+    status
+}
+
+/** Izpise obvestila vseh faz skupaj z izvorno kodo in vrne izhodni status prve zabelezene napake.  */
+pub fn emit_diagnostics(result : &CompilationResult, options : &Options) -> i32
+{
+    let mut sources = SourceCache::new_with_tab_width(options.tab_width);
+    for diagnostic in &result.diagnostics
+    {
+        if diagnostic.is_error() || !options.is_quiet()
+        {
+            eprintln!("{}", snippet::render(diagnostic, &mut sources));
+        }
+    }
+    match result.get_exit_code()
+    {
+        Some(exit_code) => exit_code.get_status(),
+        None => EXIT_SUCCESS,
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+
+/** Oznaka niza v tabeli imen (identifikatorja, rezervirane besede ali imena datoteke).
+ *
+ * Enaka niza imata vedno enako oznako, zato se imena primerjajo in razprsujejo kot stevila.
+ * Tabela imen je ena za ves proces, zato lahko oznake, ustvarjene v eni niti, uporablja tudi druga nit.
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Name(u32);
+
+// preprosta zgoscevalna funkcija (kot FxHash); imena so kratka, zato je privzeti SipHash prepocasen
+#[derive(Default)]
+struct NameHasher
+{
+    hash : u64,
+}
+
+impl Hasher for NameHasher
+{
+    fn write(&mut self, bytes : &[u8])
+    {
+        for chunk in bytes.chunks(8)
+        {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, byte : u8)
+    {
+        self.add(u64::from(byte));
+    }
+
+    fn write_usize(&mut self, value : usize)
+    {
+        self.add(value as u64);
+    }
+
+    fn finish(&self) -> u64
+    {
+        self.hash
+    }
+}
+
+impl NameHasher
+{
+    fn add(&mut self, word : u64)
+    {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+// tabela imen je lastnica nizov; vsak razlicen niz hrani le enkrat
+struct Interner
+{
+    names : HashMap<Arc<str>, Name, BuildHasherDefault<NameHasher>>,
+    strings : Vec<Arc<str>>,
+}
+
+static INTERNER : OnceLock<RwLock<Interner>> = OnceLock::new();
+
+// tabela imen se ustvari ob prvi uporabi
+fn get_interner() -> &'static RwLock<Interner>
+{
+    INTERNER.get_or_init(|| RwLock::new(Interner { names : HashMap::default(), strings : Vec::new() }))
+}
+
+impl Name
+{
+    /** Vrne oznako niza; niz, ki ga tabela imen se ne vsebuje, se vanjo doda.
+    * @param string Niz.
+    * @return Oznaka niza.
+    */
+    pub fn new(string : &str) -> Name
+    {
+        Self::new_with_text(string).0
+    }
+
+    /** Vrne oznako niza skupaj z nizom iz tabele imen, ki ga lahko klicatelj bere brez zaklepanja.
+    * @param string Niz.
+    * @return Oznaka niza in niz, ki ga hrani tabela imen.
+    */
+    pub fn new_with_text(string : &str) -> (Name, Arc<str>)
+    {
+        if let Some((text, &name)) = get_interner().read().unwrap_or_else(|poisoned| poisoned.into_inner()).names.get_key_value(string)
+        {
+            return (name, text.clone());
+        }
+        let mut interner = get_interner().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // med branjem in pisanjem je niz lahko dodala druga nit
+        if let Some((text, &name)) = interner.names.get_key_value(string)
+        {
+            return (name, text.clone());
+        }
+        let text : Arc<str> = Arc::from(string);
+        let name = Name(interner.strings.len() as u32);
+        interner.strings.push(text.clone());
+        interner.names.insert(text.clone(), name);
+        (name, text)
+    }
+
+    /** Vrne niz, ki mu pripada ta oznaka; ker zaklene tabelo imen, ga uporabljajo le izpisi. */
+    pub fn as_str(self) -> Arc<str>
+    {
+        get_interner().read().unwrap_or_else(|poisoned| poisoned.into_inner()).strings[self.0 as usize].clone()
+    }
+
+    /** Vrne zaporedno stevilko niza v tabeli imen. */
+    pub fn get_index(self) -> u32
+    {
+        self.0
+    }
+}
+
+impl fmt::Display for Name
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Name
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{:?}", self.as_str())
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+use std::sync::Arc;
+use interner::Name;
+use lexanal::symbol::Token;
+use lexanal::token_table::{CharClass, Regex, TokenPattern, TOKEN_TABLE};
+
+
+// stolpci prehodne tabele: znaki ASCII, ne-ASCII crke, ne-ASCII kontrolni znaki in ostali ne-ASCII znaki
+const NON_ASCII_LETTER : usize = 128;
+const NON_ASCII_CONTROL : usize = 129;
+const NON_ASCII_OTHER : usize = 130;
+const COLUMNS : usize = 131;
+// ni prehoda
+const DEAD : usize = usize::MAX;
+
+fn get_column(chr : char) -> usize
+{
+    if chr.is_ascii()
+    {
+        chr as usize
+    }
+    else if chr.is_alphabetic()
+    {
+        NON_ASCII_LETTER
+    }
+    else if chr.is_control()
+    {
+        NON_ASCII_CONTROL
+    }
+    else
+    {
+        NON_ASCII_OTHER
+    }
+}
+
+// znak, ki predstavlja vse znake stolpca
+fn get_representative(column : usize) -> char
+{
+    match column
+    {
+        NON_ASCII_LETTER => '\u{E9}',
+        NON_ASCII_CONTROL => '\u{85}',
+        NON_ASCII_OTHER => '\u{A0}',
+        column => column as u8 as char,
+    }
+}
+
+enum Edge
+{
+    Char(char),
+    Class(CharClass),
+}
+
+impl Edge
+{
+    fn matches(&self, chr : char) -> bool
+    {
+        match *self
+        {
+            Edge::Char(edge_chr) => edge_chr == chr,
+            Edge::Class(class) => class.contains(chr),
+        }
+    }
+}
+
+// nedeterministicni avtomat; stanje 0 je zacetno
+struct Nfa
+{
+    edges : Vec<Vec<(Edge, usize)>>,
+    // prazni prehodi (brez znaka)
+    empty_edges : Vec<Vec<usize>>,
+    // indeks vzorca v tabeli, ki ga stanje sprejme
+    accepting : Vec<Option<usize>>,
+}
+
+impl Nfa
+{
+    fn new() -> Nfa
+    {
+        Nfa { edges : vec![Vec::new()], empty_edges : vec![Vec::new()], accepting : vec![None] }
+    }
+
+    fn add_state(&mut self) -> usize
+    {
+        self.edges.push(Vec::new());
+        self.empty_edges.push(Vec::new());
+        self.accepting.push(None);
+        self.edges.len() - 1
+    }
+
+    fn add_pattern(&mut self, index : usize, pattern : TokenPattern)
+    {
+        match pattern
+        {
+            TokenPattern::Literal(text) =>
+            {
+                assert!(!text.is_empty(), "Literal token pattern must not be empty");
+                let state = self.add_text(0, text);
+                self.accepting[state] = Some(index);
+            },
+            TokenPattern::Repeat(first, rest) =>
+            {
+                let state = self.add_state();
+                self.edges[0].push((Edge::Class(first), state));
+                self.edges[state].push((Edge::Class(rest), state));
+                self.accepting[state] = Some(index);
+            },
+            TokenPattern::Regex(regex) =>
+            {
+                let state = self.add_regex(0, regex);
+                assert!(state != 0, "Token pattern {:?} must not match the empty string", regex);
+                self.accepting[state] = Some(index);
+            },
+        }
+    }
+
+    // doda pot za besedilo iz stanja from in vrne stanje na njenem koncu
+    fn add_text(&mut self, from : usize, text : &str) -> usize
+    {
+        assert!(text.is_ascii(), "Token pattern text '{}' must be an ASCII string", text);
+        let mut state = from;
+        for chr in text.chars()
+        {
+            let next = self.add_state();
+            self.edges[state].push((Edge::Char(chr), next));
+            state = next;
+        }
+        state
+    }
+
+    /** Doda avtomat za regularni izraz, ki se zacne v stanju <code>from</code>, in vrne njegovo koncno stanje.
+    *
+    * Prazni prehodi vodijo le v nova stanja, zato se izrazi, ki se zacnejo v istem stanju, ne mesajo.
+    */
+    fn add_regex(&mut self, from : usize, regex : Regex) -> usize
+    {
+        match regex
+        {
+            Regex::Text(text) => self.add_text(from, text),
+            Regex::Class(class) =>
+            {
+                let state = self.add_state();
+                self.edges[from].push((Edge::Class(class), state));
+                state
+            },
+            Regex::Sequence(parts) => parts.iter().fold(from, |state, &part| self.add_regex(state, part)),
+            Regex::Choice(alternatives) =>
+            {
+                let end = self.add_state();
+                for &alternative in alternatives
+                {
+                    let state = self.add_regex(from, alternative);
+                    self.empty_edges[state].push(end);
+                }
+                end
+            },
+            Regex::Optional(&optional) =>
+            {
+                let end = self.add_state();
+                let state = self.add_regex(from, optional);
+                self.empty_edges[from].push(end);
+                self.empty_edges[state].push(end);
+                end
+            },
+            Regex::Repeat(&repeated) =>
+            {
+                let loop_state = self.add_state();
+                self.empty_edges[from].push(loop_state);
+                let state = self.add_regex(loop_state, repeated);
+                self.empty_edges[state].push(loop_state);
+                loop_state
+            },
+        }
+    }
+
+    // doda stanja, dosegljiva s praznimi prehodi
+    fn close(&self, mut states : BTreeSet<usize>) -> BTreeSet<usize>
+    {
+        let mut stack : Vec<usize> = states.iter().cloned().collect();
+        while let Some(state) = stack.pop()
+        {
+            for &next in &self.empty_edges[state]
+            {
+                if states.insert(next)
+                {
+                    stack.push(next);
+                }
+            }
+        }
+        states
+    }
+}
+
+/** Deterministicni koncni avtomat, ki prepozna osnovne simbole iz tabele vzorcev.
+ *
+ * Avtomat se zgradi iz nedeterministicnega avtomata vseh vzorcev (s praznimi prehodi za regularne izraze)
+ * s konstrukcijo podmnozic. Stanje sprejme
+ * simbol prvega vzorca v tabeli, ki se konca v njem.
+ */
+pub struct TokenDfa
+{
+    transitions : Vec<[usize; COLUMNS]>,
+    tokens : Vec<Option<Token>>,
+    // besedilo sprejetega simbola in njegova oznaka v tabeli imen, ce je vzorec dolocen z besedilom (rezervirane besede, operatorji, locila)
+    lexemes : Vec<Option<(Name, Arc<str>)>>,
+}
+
+impl TokenDfa
+{
+    /** Zgradi avtomat iz tabele vzorcev.
+    * @param table Osnovni simboli in njihovi vzorci; prej navedeni vzorci imajo prednost.
+    */
+    pub fn new(table : &[(Token, TokenPattern)]) -> TokenDfa
+    {
+        let mut nfa = Nfa::new();
+        for (index, &(_, pattern)) in table.iter().enumerate()
+        {
+            nfa.add_pattern(index, pattern);
+        }
+
+        let start = nfa.close([0].iter().cloned().collect());
+        let mut ids : HashMap<BTreeSet<usize>, usize> = HashMap::new();
+        ids.insert(start.clone(), 0);
+        let mut sets = vec![start];
+        let mut dfa = TokenDfa { transitions : Vec::new(), tokens : Vec::new(), lexemes : Vec::new() };
+        while dfa.transitions.len() < sets.len()
+        {
+            let current = sets[dfa.transitions.len()].clone();
+            let mut row = [DEAD; COLUMNS];
+            for (column, target) in row.iter_mut().enumerate()
+            {
+                let chr = get_representative(column);
+                let next : BTreeSet<usize> = current.iter()
+                    .flat_map(|&state| nfa.edges[state].iter())
+                    .filter(|&(edge, _)| edge.matches(chr))
+                    .map(|&(_, state)| state)
+                    .collect();
+                let next = nfa.close(next);
+                if next.is_empty()
+                {
+                    continue;
+                }
+                *target = match ids.get(&next)
+                {
+                    Some(&id) => id,
+                    None =>
+                    {
+                        sets.push(next.clone());
+                        ids.insert(next, sets.len() - 1);
+                        sets.len() - 1
+                    },
+                };
+            }
+            let accepted = current.iter().filter_map(|&state| nfa.accepting[state]).min();
+            let lexeme = match accepted.map(|index| table[index].1)
+            {
+                Some(TokenPattern::Literal(text)) => Some(Name::new_with_text(text)),
+                _ => None,
+            };
+            dfa.transitions.push(row);
+            dfa.tokens.push(accepted.map(|index| table[index].0));
+            dfa.lexemes.push(lexeme);
+        }
+        dfa
+    }
+
+    /** Vrne avtomat za <code>TOKEN_TABLE</code>, ki se zgradi ob prvi uporabi v niti.  */
+    pub fn shared() -> Rc<TokenDfa>
+    {
+        thread_local!(static DFA : Rc<TokenDfa> = Rc::new(TokenDfa::new(TOKEN_TABLE)));
+        DFA.with(|dfa| dfa.clone())
+    }
+
+    pub fn get_start_state(&self) -> usize
+    {
+        0
+    }
+
+    /** Vrne stanje po prehodu z znakom <code>chr</code> ali <code>None</code>, ce prehoda ni.  */
+    pub fn get_next_state(&self, state : usize, chr : char) -> Option<usize>
+    {
+        match self.transitions[state][get_column(chr)]
+        {
+            DEAD => None,
+            next => Some(next),
+        }
+    }
+
+    /** Vrne simbol, ki ga stanje sprejme, ali <code>None</code>, ce stanje ni koncno.  */
+    pub fn get_token(&self, state : usize) -> Option<Token>
+    {
+        self.tokens[state]
+    }
+
+    /** Vrne oznako in besedilo simbola, ki ga stanje sprejme, ce ga vzorec natanko doloca (sicer <code>None</code>).  */
+    pub fn get_lexeme(&self, state : usize) -> Option<(Name, Arc<str>)>
+    {
+        self.lexemes[state].clone()
+    }
+
+    pub fn get_state_count(&self) -> usize
+    {
+        self.transitions.len()
+    }
+}
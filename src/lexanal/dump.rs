@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use lexanal::position::Position;
+use lexanal::symbol::{LiteralValue, Symbol};
+use xml::XMLable;
+
+
+/** Oblika izpisa osnovnih simbolov, ki jih vrne leksikalni analizator. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DumpFormat
+{
+    // lexanal.xml, kot ga prikaze xsl/lexanal.xsl
+    Xml,
+    // en JSON objekt v vsaki vrstici
+    Json,
+    // ena vrstica na simbol, npr. 3:5-3:7 IDENTIFIER fib
+    Text,
+}
+
+impl DumpFormat
+{
+    /** Vrne koncnico izhodne datoteke. */
+    pub fn get_extension(&self) -> &'static str
+    {
+        match *self
+        {
+            DumpFormat::Xml => "xml",
+            DumpFormat::Json => "jsonl",
+            DumpFormat::Text => "txt",
+        }
+    }
+}
+
+impl FromStr for DumpFormat
+{
+    type Err = String;
+
+    fn from_str(format : &str) -> Result<DumpFormat, String>
+    {
+        match format
+        {
+            "xml" => Ok(DumpFormat::Xml),
+            "json" => Ok(DumpFormat::Json),
+            "text" => Ok(DumpFormat::Text),
+            _ => Err(format!("Unknown format '{}'", format)),
+        }
+    }
+}
+
+impl fmt::Display for DumpFormat
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            DumpFormat::Xml => write!(f, "xml"),
+            DumpFormat::Json => write!(f, "json"),
+            DumpFormat::Text => write!(f, "text"),
+        }
+    }
+}
+
+/** Izpise osnovni simbol v izbrani obliki.
+* @param symbol Osnovni simbol.
+* @param format Oblika izpisa.
+* @param out Izhodni tok; pri obliki XML mora biti glava dokumenta ze izpisana (glej <code>ProteusXmlCreator</code>).
+*/
+pub fn write_symbol(symbol : &Symbol, format : DumpFormat, out : &mut Write) -> io::Result<()>
+{
+    match format
+    {
+        DumpFormat::Xml =>
+        {
+            symbol.to_xml(out);
+            Ok(())
+        },
+        DumpFormat::Json => writeln!(out, "{}", symbol_to_json(symbol)),
+        DumpFormat::Text => writeln!(out, "{}", symbol_to_text(symbol)),
+    }
+}
+
+/** Vrne simbol kot JSON objekt z vrsto simbola, besedilom, vrednostjo konstante in polozajem. */
+pub fn symbol_to_json(symbol : &Symbol) -> String
+{
+    let value = match symbol.get_value()
+    {
+        Some(LiteralValue::Int(value)) => value.to_string(),
+        Some(LiteralValue::Real(value)) if value.is_finite() => format!("{:?}", value),
+        Some(LiteralValue::Bool(value)) => value.to_string(),
+        Some(LiteralValue::Str(value)) => str_to_json(value),
+        Some(LiteralValue::Real(_)) | None => String::from("null"),
+    };
+    format!("{{\"token\":\"{:?}\",\"lexeme\":{},\"value\":{},\"position\":{}}}",
+            symbol.get_token(), str_to_json(symbol.get_ref_lexeme()), value, position_to_json(symbol.get_ref_position()))
+}
+
+/** Vrne polozaj kot JSON objekt z vrsticami, stolpci in odmiki. */
+pub fn position_to_json(position : &Position) -> String
+{
+    format!("{{\"filename\":{},\"begin_line\":{},\"begin_column\":{},\"begin_offset\":{},\
+             \"end_line\":{},\"end_column\":{},\"end_offset\":{}}}",
+            str_to_json(&position.get_filename()), position.get_begin_line(), position.get_begin_column(),
+            position.get_begin_offset(), position.get_end_line(), position.get_end_column(), position.get_end_offset())
+}
+
+/** Vrne simbol kot vrstico oblike <code>vrstica:stolpec-vrstica:stolpec VRSTA besedilo</code>.
+ *
+ * Konci vrstic in tabulatorji v besedilu (npr. v vecvrsticnih nizih) so zapisani kot <code>\n</code>,
+ * <code>\r</code> in <code>\t</code>, da vsak simbol ostane v svoji vrstici.
+ */
+pub fn symbol_to_text(symbol : &Symbol) -> String
+{
+    let position = symbol.get_ref_position();
+    let mut lexeme = String::new();
+    for chr in symbol.get_ref_lexeme().chars()
+    {
+        match chr
+        {
+            '\n' => lexeme.push_str("\\n"),
+            '\r' => lexeme.push_str("\\r"),
+            '\t' => lexeme.push_str("\\t"),
+            chr => lexeme.push(chr),
+        }
+    }
+    format!("{}:{}-{}:{} {:?} {}", position.get_begin_line(), position.get_begin_column(),
+            position.get_end_line(), position.get_end_column(), symbol.get_token(), lexeme)
+}
+
+/** Vrne niz kot JSON niz (v narekovajih in z ubeznimi zaporedji). */
+pub fn str_to_json(string : &str) -> String
+{
+    let mut json = String::from("\"");
+    for chr in string.chars()
+    {
+        match chr
+        {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            chr if (chr as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", chr as u32)),
+            chr => json.push(chr),
+        }
+    }
+    json.push('"');
+    json
+}
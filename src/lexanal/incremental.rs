@@ -0,0 +1,267 @@
+use std::mem;
+use lexanal::{LexicalAnalyzer, Location, DEFAULT_TAB_WIDTH};
+use lexanal::position::Position;
+use lexanal::symbol::Symbol;
+use lexanal::trivia::Trivia;
+use report::Diagnostic;
+
+
+/** Sprememba besedila: del izvorne kode med odmikoma se zamenja z novim besedilom. */
+#[derive(Debug, Clone)]
+pub struct TextEdit
+{
+    // odmik prvega zamenjanega bajta
+    pub begin_offset : usize,
+    // odmik prvega bajta za zamenjanim delom
+    pub end_offset : usize,
+    pub text : String,
+}
+
+impl TextEdit
+{
+    pub fn new(begin_offset : usize, end_offset : usize, text : &str) -> TextEdit
+    {
+        TextEdit { begin_offset, end_offset, text : text.to_string() }
+    }
+}
+
+/** Simboli, ki jih je po spremembi zamenjala ponovna leksikalna analiza. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RelexedSymbols
+{
+    // indeks prvega ponovno prebranega simbola
+    pub first : usize,
+    // stevilo odstranjenih starih simbolov
+    pub removed : usize,
+    // stevilo novih simbolov od indeksa first naprej
+    pub inserted : usize,
+}
+
+/** Izvorna koda skupaj z osnovnimi simboli in leksikalnimi napakami, ki se po spremembi besedila posodobijo.
+ *
+ * Sprememba ponovno prebere le del besedila: analiza se zacne za zadnjim simbolom, ki se konca v kateri od
+ * vrstic pred spremembo (tam ni odprtega niza ali komentarja), in se ustavi pri prvem novem simbolu za
+ * spremembo, ki je enak staremu simbolu na istem (premaknjenem) mestu. Simboli za njim se ne preberejo
+ * ponovno, temvec se le premaknejo za spremenjeno stevilo vrstic in bajtov.
+ */
+pub struct LexedDocument
+{
+    file_name : String,
+    source : String,
+    tab_width : u64,
+    lossless : bool,
+    symbols : Vec<Symbol>,
+    end_trivia : Vec<Trivia>,
+    diagnostics : Vec<Diagnostic>,
+}
+
+// polozaj zadnjega dela simbola skupaj s sledecimi deli (blocni komentar se lahko konca v kasnejsi vrstici);
+// za njim se lahko leksikalna analiza nadaljuje
+fn get_last_position(symbol : &Symbol) -> &Position
+{
+    match symbol.get_trailing_trivia().last()
+    {
+        Some(trivia) => trivia.get_position(),
+        None => symbol.get_ref_position(),
+    }
+}
+
+fn get_begin_offset(diagnostic : &Diagnostic) -> Option<usize>
+{
+    diagnostic.position.as_ref().map(|position| position.get_begin_offset())
+}
+
+fn shift_diagnostic(diagnostic : &mut Diagnostic, line_delta : i64, offset_delta : isize)
+{
+    if let Some(position) = diagnostic.position.as_mut()
+    {
+        position.shift(line_delta, offset_delta);
+    }
+    for label in &mut diagnostic.labels
+    {
+        label.position.shift(line_delta, offset_delta);
+    }
+}
+
+// poisce stari simbol, ki je enak novemu simbolu za spremembo, in vrne njegov indeks in spremembo stevilk vrstic;
+// stari simboli pred novim simbolom se preskocijo
+fn find_synced(old_symbols : &[Symbol], next_old : &mut usize, symbol : &Symbol, offset_delta : isize) -> Option<(usize, i64)>
+{
+    let position = symbol.get_ref_position();
+    let shifted_offset = |old : &Symbol| (old.get_ref_position().get_begin_offset() as isize + offset_delta) as usize;
+    while *next_old < old_symbols.len() && shifted_offset(&old_symbols[*next_old]) < position.get_begin_offset()
+    {
+        *next_old += 1;
+    }
+    let old = old_symbols.get(*next_old)?;
+    let old_position = old.get_ref_position();
+    if shifted_offset(old) == position.get_begin_offset() && old_position.get_begin_column() == position.get_begin_column()
+       && old.get_token() == symbol.get_token() && old.get_ref_lexeme() == symbol.get_ref_lexeme()
+    {
+        Some((*next_old, position.get_begin_line() as i64 - old_position.get_begin_line() as i64))
+    }
+    else
+    {
+        None
+    }
+}
+
+impl LexedDocument
+{
+    /** Prebere vse osnovne simbole izvorne kode s privzeto sirino tabulatorja.
+    * @param file_name Ime, ki se uporabi kot ime datoteke v polozajih simbolov.
+    * @param source Izvorna koda.
+    */
+    pub fn new(file_name : String, source : String) -> LexedDocument
+    {
+        Self::new_with_options(file_name, source, DEFAULT_TAB_WIDTH, false)
+    }
+
+    /** Prebere vse osnovne simbole izvorne kode.
+    * @param file_name Ime, ki se uporabi kot ime datoteke v polozajih simbolov.
+    * @param source Izvorna koda.
+    * @param tab_width Sirina tabulatorja (glej <code>LexicalAnalyzer::set_tab_width</code>).
+    * @param lossless Ali simboli nosijo presledke in komentarje (glej <code>LexicalAnalyzer::set_lossless</code>).
+    */
+    pub fn new_with_options(file_name : String, source : String, tab_width : u64, lossless : bool) -> LexedDocument
+    {
+        let mut document = LexedDocument { file_name, source, tab_width, lossless, symbols : Vec::new(),
+                                           end_trivia : Vec::new(), diagnostics : Vec::new() };
+        let mut lexer = document.get_lexer();
+        while let Ok(Some(symbol)) = lexer.get_next_symbol()
+        {
+            document.symbols.push(symbol);
+        }
+        document.end_trivia = lexer.get_end_trivia().to_vec();
+        document.diagnostics = lexer.get_diagnostics().get_diagnostics();
+        document
+    }
+
+    pub fn get_source(&self) -> &str
+    {
+        &self.source
+    }
+
+    pub fn get_symbols(&self) -> &[Symbol]
+    {
+        &self.symbols
+    }
+
+    /** Vrne presledke in komentarje za zadnjim simbolom (le v nacinu brez izgub).  */
+    pub fn get_end_trivia(&self) -> &[Trivia]
+    {
+        &self.end_trivia
+    }
+
+    /** Vrne leksikalne napake, urejene po polozaju.  */
+    pub fn get_diagnostics(&self) -> &[Diagnostic]
+    {
+        &self.diagnostics
+    }
+
+    fn get_lexer(&self) -> LexicalAnalyzer
+    {
+        let mut lexer = LexicalAnalyzer::new_from_str(self.file_name.clone(), &self.source);
+        lexer.set_tab_width(self.tab_width);
+        lexer.set_lossless(self.lossless);
+        lexer
+    }
+
+    /** Spremeni besedilo in ponovno prebere le simbole, na katere sprememba vpliva.
+    * @param edit Sprememba; odmika morata biti na mejah znakov UTF-8.
+    * @return Simboli, ki jih je zamenjala ponovna analiza, ali opis napake, ce sprememba ni veljavna.
+    */
+    pub fn apply_edit(&mut self, edit : &TextEdit) -> Result<RelexedSymbols, String>
+    {
+        if edit.begin_offset > edit.end_offset || edit.end_offset > self.source.len()
+           || !self.source.is_char_boundary(edit.begin_offset) || !self.source.is_char_boundary(edit.end_offset)
+        {
+            return Err(format!("Invalid edit range {}..{}", edit.begin_offset, edit.end_offset));
+        }
+
+        // ohranijo se simboli do zadnjega, za katerim je pred spremembo se konec vrstice
+        let before_edit = self.symbols.partition_point(|symbol| symbol.get_ref_position().get_end_offset() < edit.begin_offset);
+        let mut kept = 0;
+        let mut restart = 0;
+        let mut restart_line = 1;
+        for index in (0..before_edit).rev()
+        {
+            let last_position = get_last_position(&self.symbols[index]);
+            let end = last_position.get_end_offset();
+            // sledeci deli simbola (komentar do konca vrstice) lahko segajo v spremembo
+            if end <= edit.begin_offset && self.source.as_bytes()[end..edit.begin_offset].iter().any(|&byte| byte == b'\n' || byte == b'\r')
+            {
+                kept = index + 1;
+                restart = end;
+                restart_line = last_position.get_end_line();
+                break;
+            }
+        }
+        let line_start = self.source.as_bytes()[..restart].iter().rposition(|&byte| byte == b'\n' || byte == b'\r').map_or(0, |index| index + 1);
+
+        self.source.replace_range(edit.begin_offset..edit.end_offset, &edit.text);
+        let offset_delta = edit.text.len() as isize - (edit.end_offset - edit.begin_offset) as isize;
+        let edit_end = edit.begin_offset + edit.text.len();
+
+        // analizator se postavi na zacetek vrstice in z branjem znakov do mesta nadaljevanja izracuna stolpec
+        let mut lexer = self.get_lexer();
+        let line_begin = Location { line : restart_line, column : 1, offset : line_start };
+        lexer.set_cursor((line_begin, line_begin));
+        while lexer.offset < restart
+        {
+            lexer.next_char();
+        }
+
+        let mut new_symbols = Vec::new();
+        let mut next_old = 0;
+        // indeks starega simbola (od kept naprej), s katerim se je analiza ujela, in sprememba stevilk vrstic
+        let mut synced : Option<(usize, i64)> = None;
+        while let Ok(Some(symbol)) = lexer.get_next_symbol()
+        {
+            if symbol.get_ref_position().get_begin_offset() >= edit_end
+            {
+                synced = find_synced(&self.symbols[kept..], &mut next_old, &symbol, offset_delta);
+            }
+            new_symbols.push(symbol);
+            if synced.is_some()
+            {
+                break;
+            }
+        }
+
+        let inserted = new_symbols.len();
+        let (mut diagnostics, old_diagnostics) : (Vec<Diagnostic>, Vec<Diagnostic>) = mem::take(&mut self.diagnostics).into_iter()
+            .partition(|diagnostic| get_begin_offset(diagnostic).map_or(true, |offset| offset < restart));
+        diagnostics.extend(lexer.get_diagnostics().get_diagnostics());
+        let removed = match synced
+        {
+            Some((index, line_delta)) =>
+            {
+                // staro besedilo za zadnjim ponovno prebranim simbolom je nespremenjeno
+                let reused_from = get_last_position(&self.symbols[kept + index]).get_end_offset();
+                for mut diagnostic in old_diagnostics.into_iter().filter(|diagnostic| get_begin_offset(diagnostic) >= Some(reused_from))
+                {
+                    shift_diagnostic(&mut diagnostic, line_delta, offset_delta);
+                    diagnostics.push(diagnostic);
+                }
+                for symbol in &mut self.symbols[kept + index + 1..]
+                {
+                    symbol.shift(line_delta, offset_delta);
+                }
+                for trivia in &mut self.end_trivia
+                {
+                    trivia.shift(line_delta, offset_delta);
+                }
+                index + 1
+            },
+            None =>
+            {
+                self.end_trivia = lexer.get_end_trivia().to_vec();
+                self.symbols.len() - kept
+            },
+        };
+        self.symbols.splice(kept..kept + removed, new_symbols);
+        self.diagnostics = diagnostics;
+        Ok(RelexedSymbols { first : kept, removed, inserted })
+    }
+}
@@ -0,0 +1,46 @@
+use std::io;
+use std::error;
+use std::fmt::Display;
+use std::fmt;
+
+
+/** Napaka, zaradi katere leksikalna analiza ne more nadaljevati.
+ *
+ * Leksikalne napake v izvorni kodi niso med njimi: leksikalni analizator jih zabelezi v zbiralniku
+ * obvestil, namesto napacnega dela vhodne datoteke pa vrne simbol <code>Token::ERROR</code>.
+ */
+#[derive(Debug)]
+pub enum LexError
+{
+    IoError(io::Error),
+}
+
+impl Display for LexError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            LexError::IoError(ref ioe) => write!(f, "IO error: {}", ioe),
+        }
+    }
+}
+
+impl error::Error for LexError
+{
+    fn source(&self) -> Option<&(error::Error + 'static)>
+    {
+        match *self
+        {
+            LexError::IoError(ref ioe) => Some(ioe),
+        }
+    }
+}
+
+impl From<io::Error> for LexError
+{
+    fn from(err : io::Error) -> LexError
+    {
+        LexError::IoError(err)
+    }
+}
@@ -0,0 +1,148 @@
+use lexanal::symbol::{LiteralValue, Token};
+
+
+/** Najvecje stevilo bajtov, ki jih lahko zasede ubezno zaporedje za znakom <code>\</code> (npr. <code>u{10FFFF}</code>). */
+pub const MAX_ESCAPE_LENGTH : usize = 10;
+
+/** Izracuna vrednost konstante, ki jo je prepoznal koncni avtomat iz <code>TOKEN_TABLE</code>.
+ *
+ * Avtomat zagotovi obliko konstante, zato je napaka le vrednost, ki je ni mogoce predstaviti
+ * (npr. preveliko celo stevilo ali <code>\u{D800}</code>).
+ * @param token Vrsta konstante.
+ * @param text Besedilo konstante, kot je zapisano v vhodni datoteki.
+ * @return Vrednost konstante ali opis napake.
+ */
+pub fn decode(token : Token, text : &str) -> Result<LiteralValue, String>
+{
+    match token
+    {
+        Token::BOOLCONST => Ok(LiteralValue::Bool(text == "true")),
+        Token::INTCONST => decode_int(text).map(LiteralValue::Int),
+        Token::REALCONST => decode_real(text).map(LiteralValue::Real),
+        Token::STRINGCONST => decode_string(text).map(LiteralValue::Str),
+        token => Err(format!("{} is not a constant", token)),
+    }
+}
+
+/** Vrne vrednost celostevilske konstante z neobvezno predpono <code>0x</code>, <code>0b</code> ali <code>0o</code>.
+ *
+ * Konstanta nima predznaka (<code>-</code> je operator), zato najmanjse celo stevilo ni konstanta;
+ * zapise se kot <code>-9223372036854775807 - 1</code>.
+ */
+pub fn decode_int(text : &str) -> Result<i64, String>
+{
+    let (radix, digits) = match text.get(..2)
+    {
+        Some("0x") => (16, &text[2..]),
+        Some("0b") => (2, &text[2..]),
+        Some("0o") => (8, &text[2..]),
+        _ => (10, text),
+    };
+    let digits : String = digits.chars().filter(|&c| c != '_').collect();
+    match i64::from_str_radix(&digits, radix)
+    {
+        Ok(value) => Ok(value),
+        Err(_) if u64::from_str_radix(&digits, radix) == Ok(i64::MIN.unsigned_abs()) =>
+            Err(format!("Integer constant {} is out of range (at most {}); write the smallest integer as -{} - 1", text, i64::MAX, i64::MAX)),
+        Err(_) => Err(format!("Integer constant {} is out of range (at most {})", text, i64::MAX)),
+    }
+}
+
+/** Vrne vrednost realne konstante, ki mora biti koncna in, ce ni enaka 0, razlicna od 0 tudi po zaokrozitvi. */
+pub fn decode_real(text : &str) -> Result<f64, String>
+{
+    let digits : String = text.chars().filter(|&c| c != '_').collect();
+    let value : f64 = digits.parse().unwrap_or(f64::INFINITY);
+    // nenicelna konstanta, ki se zaokrozi na 0, je premajhna
+    let mantissa_is_zero = digits.split(['e', 'E']).next().unwrap_or("").chars().all(|c| c == '0' || c == '.');
+    if value.is_infinite() || (value == 0.0 && !mantissa_is_zero)
+    {
+        return Err(format!("Real constant {} is out of range", text));
+    }
+    Ok(value)
+}
+
+/** Vrne vrednost znakovne konstante skupaj z narekovajema ali opis prvega napacnega ubeznega zaporedja. */
+pub fn decode_string(text : &str) -> Result<String, String>
+{
+    let mut value = String::new();
+    let mut rest = text.strip_prefix('"').and_then(|text| text.strip_suffix('"')).unwrap_or(text);
+    while let Some(chr) = rest.chars().next()
+    {
+        rest = &rest[chr.len_utf8()..];
+        if chr == '\\'
+        {
+            let (escaped, length) = decode_escape(rest);
+            value.push(escaped?);
+            rest = &rest[length..];
+        }
+        else
+        {
+            value.push(chr);
+        }
+    }
+    Ok(value)
+}
+
+/** Prebere ubezno zaporedje za znakom <code>\</code> in vrne znak, ki ga zaporedje predstavlja.
+ *
+ * Podprta so zaporedja <code>\\ \" \' \n \t \r \0 \xHH</code> (do <code>7F</code>) in <code>\u{H...}</code>.
+ * @param text Besedilo za znakom <code>\</code>.
+ * @return Predstavljeni znak ali opis napake in stevilo bajtov zaporedja, ki jih je treba preskociti.
+ */
+pub fn decode_escape(text : &str) -> (Result<char, String>, usize)
+{
+    let chr = match text.chars().next()
+    {
+        // nezakljuceno konstanto zabelezi leksikalni analizator
+        None | Some('\n') | Some('\r') => return (Err(String::from("Error: Unfinished string escape!")), 0),
+        Some(chr) => chr,
+    };
+    let rest = &text[chr.len_utf8()..];
+    match chr
+    {
+        '\\' | '\"' | '\'' => (Ok(chr), 1),
+        'n' => (Ok('\n'), 1),
+        't' => (Ok('\t'), 1),
+        'r' => (Ok('\r'), 1),
+        '0' => (Ok('\0'), 1),
+        'x' =>
+        {
+            let digits = get_hex_digits(rest, 2);
+            let result = match u32::from_str_radix(digits, 16)
+            {
+                Ok(code) if digits.len() == 2 && code <= 0x7F => Ok(code as u8 as char),
+                Ok(_) if digits.len() == 2 => Err(format!("Error: String escape \\x{} is out of range (at most \\x7F)!", digits)),
+                _ => Err(String::from("Error: String escape \\x must be followed by two hexadecimal digits!")),
+            };
+            (result, 1 + digits.len())
+        },
+        'u' =>
+        {
+            let braced = match rest.strip_prefix('{')
+            {
+                Some(braced) => braced,
+                None => return (Err(String::from("Error: String escape \\u must be followed by {!")), 1),
+            };
+            let digits = get_hex_digits(braced, 6);
+            if !braced[digits.len()..].starts_with('}')
+            {
+                return (Err(String::from("Error: String escape \\u{ must contain one to six hexadecimal digits and end with }!")), 2 + digits.len());
+            }
+            let result = match u32::from_str_radix(digits, 16).ok().and_then(char::from_u32)
+            {
+                Some(chr) => Ok(chr),
+                None => Err(format!("Error: String escape \\u{{{}}} is not a valid Unicode character!", digits)),
+            };
+            (result, 3 + digits.len())
+        },
+        c => (Err(format!("Error: String escape \\{}!", c.escape_default())), c.len_utf8()),
+    }
+}
+
+// najvec max_count sestnajstiskih stevk na zacetku besedila
+fn get_hex_digits(text : &str, max_count : usize) -> &str
+{
+    let count = text.bytes().take(max_count).take_while(u8::is_ascii_hexdigit).count();
+    &text[..count]
+}
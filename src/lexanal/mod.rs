@@ -1,77 +1,105 @@
 pub mod symbol;
 pub mod position;
+pub mod lex_error;
+pub mod trivia;
+pub mod token_table;
+pub mod dfa;
+pub mod literal;
+pub mod dump;
+pub mod incremental;
 pub mod run;
 
 use std::io;
-use std::io::{Read,BufReader, Seek,SeekFrom};
+use std::io::Read;
+use std::str;
+use std::char;
+use std::cmp;
+use std::mem;
 use std::fs::File;
 use std::rc::Rc;
-use std::collections::HashMap;
-use lexanal::symbol::{Symbol,Token};
+use std::sync::Arc;
+use std::collections::VecDeque;
+use lexanal::symbol::{LiteralValue, Symbol, Token};
 use self::position::Position;
-use report::ExitCode;
-use report;
+use self::lex_error::LexError;
+use self::trivia::{Trivia, TriviaKind};
+use self::dfa::TokenDfa;
+use self::literal::MAX_ESCAPE_LENGTH;
+use report::{Diagnostic, Diagnostics, ExitCode};
+use interner::Name;
 
+/** Privzeta sirina tabulatorja: tabulator premakne stolpec do naslednjega veckratnika te sirine (plus 1). */
+pub const DEFAULT_TAB_WIDTH : u64 = 4;
+
+// mesto znaka v vhodni datoteki
 #[derive(Copy,Clone,Debug)]
-enum ParserState 
+struct Location
 {
-    InitialState,
-    IdentifierState,
-    IntConstState,
-    RealConstState,
-    RealExcpConstState,
-    EqualsState,
-    LessState,
-    GraterState,
-    CommentState,
-    StringConstState,
-    StringEscapeState,
+    line : u64,
+    column : u64,
+    offset : usize,
 }
 
 
 pub struct LexicalAnalyzer 
 {
-    file_name : Rc<String>,
-    reader : BufReader<File>,
+    file_name : Name,
+    // celotna vhodna datoteka
+    source : Vec<u8>,
+    // mesto naslednjega neprebranega bajta v vhodni datoteki
+    offset : usize,
+    // vrstica in stolpec naslednjega neprebranega znaka
     line  : u64,
     column : u64,
-    reserved_words : HashMap<&'static str, Token>
+    // mesto zadnjega prebranega znaka
+    last : Location,
+    tab_width : u64,
+    // koncni avtomat za rezervirane besede, operatorje, locila, imena in konstante
+    dfa : Rc<TokenDfa>,
+    diagnostics : Diagnostics,
+    // kopije vrnjenih simbolov, ce jih je treba zapomniti
+    recorded_symbols : Option<Vec<Symbol>>,
+    // dokumentacijski komentar, ki se pripne naslednjemu simbolu
+    pending_doc : Option<String>,
+    // ali se zapomnijo presledki, konci vrstic in komentarji
+    lossless : bool,
+    // presledki, konci vrstic in komentarji, ki se pripnejo naslednjemu simbolu
+    pending_trivia : Vec<Trivia>,
+    // ze prebrani simboli, ki jih je pogledal peek, vrnil pa se niso
+    lookahead : VecDeque<Symbol>,
 }
 
 
 impl LexicalAnalyzer {
-    fn get_reserved_keywords_hashmap() -> HashMap<&'static str, Token> 
-    {
-        let mut reserved_words : HashMap<&'static str, Token> = HashMap::new();
-        reserved_words.insert("int",Token::INT);
-        reserved_words.insert("real",Token::REAL);
-        reserved_words.insert("bool",Token::BOOL);
-        reserved_words.insert("string",Token::STRING);
-        reserved_words.insert("arr",Token::ARR);
-        reserved_words.insert("else",Token::ELSE);
-        reserved_words.insert("for",Token::FOR);
-        reserved_words.insert("fun",Token::FUN);
-        reserved_words.insert("if",Token::IF);
-        reserved_words.insert("rec",Token::REC);
-        reserved_words.insert("then",Token::THEN);
-        reserved_words.insert("typ",Token::TYP);
-        reserved_words.insert("var",Token::VAR);
-        reserved_words.insert("where",Token::WHERE);
-        reserved_words.insert("while",Token::WHILE);
-        reserved_words.insert("true",Token::BOOLCONST);
-        reserved_words.insert("false",Token::BOOLCONST);
-
-        reserved_words
-    }
-
-    fn get_symbol_type(&self, lexeme : String, begin_column : u64) -> Option<Symbol> 
+    // simbol, ki ga je prepoznal koncni avtomat; lexeme je oznaka besedila iz tabele simbolov, ce ga ta doloca
+    fn get_table_symbol(&mut self, token : Token, lexeme : Option<(Name, Arc<str>)>, begin : Location) -> Option<Symbol> 
     {
-        let p = self.get_literal_position(begin_column, lexeme.len() as u64);
-        //let r = lexeme.as_str()
-        match self.reserved_words.get(lexeme.as_str()) 
+        let p = self.get_position_from(begin);
+        // avtomat sprejme le veljavne znake, zato je besedilo kar del vhodne datoteke
+        let text = || String::from_utf8_lossy(&self.source[begin.offset..self.offset]).into_owned();
+        match token
         {
-            Some(token) => Symbol::new(*token, lexeme, p),
-            None => Symbol::new(Token::IDENTIFIER, lexeme, p),
+            Token::BOOLCONST | Token::INTCONST | Token::REALCONST | Token::STRINGCONST =>
+            {
+                let text = text();
+                match literal::decode(token, &text)
+                {
+                    Ok(value) => Symbol::new_with_value(token, &text, value, p),
+                    // scan_string napacno ubezno zaporedje (npr. \u{D800}) zabelezi na njegovem mestu
+                    Err(_) if token == Token::STRINGCONST =>
+                    {
+                        self.set_cursor((begin, self.last));
+                        self.next_char();
+                        self.scan_string(begin)
+                    },
+                    Err(msg) => self.error_symbol(&msg, &text, begin),
+                }
+            },
+            token => match lexeme
+            {
+                Some((lexeme, text)) => Symbol::new_with_name(token, lexeme, text, p),
+                None => Symbol::new(token, &text(), p),
+            },
         }
     }
  
@@ -82,342 +110,684 @@ impl LexicalAnalyzer {
     */
     pub fn new(program_name : String) -> Result<LexicalAnalyzer, String> 
     {
-        let file = File::open(&program_name);
-        let file = match file
+        Self::new_with_diagnostics(program_name, Diagnostics::new())
+    }
+
+    /** Ustvari nov leksikalni analizator, ki obvestila o napakah zapisuje v podani zbiralnik.
+    * @param programName ime datoteke z izvorno kodo programa.
+    * @param diagnostics zbiralnik obvestil.
+    */
+    pub fn new_with_diagnostics(program_name : String, diagnostics : Diagnostics) -> Result<LexicalAnalyzer, String>
+    {
+        let mut source = Vec::new();
+        match File::open(&program_name).and_then(|mut file| file.read_to_end(&mut source))
         {
-                Ok(file) => file,
+                Ok(_) => {},
                 Err(e) => return Err(format!("Error while opening file: {:?}!", e.kind())),
         };
 
-        let reader = BufReader::new(file);
-        //reader.seek(SeekFrom::Start(0));
+        Ok(Self::new_from_bytes(program_name, source, diagnostics))
+    }
+
+    /** Ustvari nov leksikalni analizator, ki bere izvorno kodo iz niza.
+    * @param program_name ime, ki se uporabi kot ime datoteke v polozajih simbolov.
+    * @param source izvorna koda programa.
+    */
+    pub fn new_from_str(program_name : String, source : &str) -> LexicalAnalyzer
+    {
+        Self::new_from_bytes(program_name, source.as_bytes().to_vec(), Diagnostics::new())
+    }
+
+    /** Ustvari nov leksikalni analizator, ki izvorno kodo prebere iz podanega bralnika.
+    * @param program_name ime, ki se uporabi kot ime datoteke v polozajih simbolov.
+    * @param reader bralnik izvorne kode programa.
+    * @returns io::Error Ce je prislo do napake pri branju.
+    */
+    pub fn new_from_reader<R : Read>(program_name : String, mut reader : R) -> Result<LexicalAnalyzer, io::Error>
+    {
+        let mut source = Vec::new();
+        reader.read_to_end(&mut source)?;
+        Ok(Self::new_from_bytes(program_name, source, Diagnostics::new()))
+    }
 
-        Ok (LexicalAnalyzer 
+    fn new_from_bytes(program_name : String, source : Vec<u8>, diagnostics : Diagnostics) -> LexicalAnalyzer
+    {
+        LexicalAnalyzer 
         {
-            file_name : Rc::new(program_name),
-            reader : reader,
+            file_name : Name::new(&program_name),
+            source,
+            offset : 0,
             line : 1, 
             column : 1,
-            reserved_words : Self::get_reserved_keywords_hashmap(),
-        })
+            last : Location { line : 1, column : 1, offset : 0 },
+            tab_width : DEFAULT_TAB_WIDTH,
+            dfa : TokenDfa::shared(),
+            diagnostics,
+            recorded_symbols : None,
+            pending_doc : None,
+            lossless : false,
+            pending_trivia : Vec::new(),
+            lookahead : VecDeque::new(),
+        }
     }
 
-    /** Vrne naslednji osnovni simbol.
-	 * 
-	 * @return Naslednji osnovni simbol ali <code>None</code> ob koncu datoteke.
-	 * @returns io::Error Ce je prislo do napake pri branju vhodne datoteke.
-	 */
-     fn get_next_char(&mut self) -> Result<Option<char> , io::Error>
+    /** Vrne ime datoteke, ki se uporablja v polozajih simbolov.  */
+    pub fn get_file_name(&self) -> Arc<str>
+    {
+        self.file_name.as_str()
+    }
+
+    pub fn get_file_name_id(&self) -> Name
+    {
+        self.file_name
+    }
+
+    pub fn get_tab_width(&self) -> u64
+    {
+        self.tab_width
+    }
+
+    /** Nastavi sirino tabulatorja, ki doloca stolpce v polozajih simbolov (privzeto <code>DEFAULT_TAB_WIDTH</code>).
+    * @param tab_width Sirina tabulatorja; vsaj 1.
+    */
+    pub fn set_tab_width(&mut self, tab_width : u64)
+    {
+        self.tab_width = cmp::max(tab_width, 1);
+    }
+
+    /** Vklopi ali izklopi nacin brez izgub.
+    *
+    * V nacinu brez izgub vsak simbol nosi presledke, konce vrstic in komentarje pred njim in za njim (glej
+    * <code>Symbol::get_full_text</code>), ostanek datoteke za zadnjim simbolom pa vrne <code>get_end_trivia</code>.
+    * Stik vseh simbolov in ostanka je enak vhodni datoteki, ce je ta veljavno zapisana v UTF-8.
+    */
+    pub fn set_lossless(&mut self, lossless : bool)
+    {
+        self.lossless = lossless;
+    }
+
+    /** Vrne presledke, konce vrstic in komentarje za zadnjim vrnjenim simbolom, ki se niso pripeti nobenemu simbolu.
+    *
+    * Ko <code>get_next_symbol</code> vrne <code>None</code>, je to konec vhodne datoteke za zadnjim simbolom.
+    */
+    pub fn get_end_trivia(&self) -> &[Trivia]
+    {
+        &self.pending_trivia
+    }
+
+    /** Od tega trenutka naprej si zapomni kopijo vsakega vrnjenega simbola.  */
+    pub fn record_symbols(&mut self)
+    {
+        if self.recorded_symbols.is_none()
+        {
+            self.recorded_symbols = Some(Vec::new());
+        }
+    }
+
+    /** Vrne do sedaj zapomnjene simbole in zacne z novim seznamom.  */
+    pub fn take_recorded_symbols(&mut self) -> Vec<Symbol>
+    {
+        self.recorded_symbols.as_mut().map(|symbols| symbols.split_off(0)).unwrap_or_default()
+    }
+
+    /** Vrne zbiralnik obvestil, v katerega leksikalni analizator zapisuje napake.  */
+    pub fn get_diagnostics(&self) -> Diagnostics
+    {
+        self.diagnostics.clone()
+    }
+
+    /** Vrne znak, ki se zacne na podanem mestu vhodne datoteke, in stevilo njegovih bajtov.
+     *
+     * Vhodna datoteka je zapisana v kodiranju UTF-8. Namesto napacnega zaporedja bajtov vrne napako
+     * in dolzino zaporedja, ki se preskoci.
+     */
+     fn decode_char(&self, offset : usize) -> Option<(Result<char, str::Utf8Error>, usize)>
+     {
+        let first = *self.source.get(offset)?;
+        if first < 0x80
+        {
+            return Some((Ok(first as char), 1));
+        }
+        let length = match first
+        {
+            0xC0 ..= 0xDF => 2,
+            0xE0 ..= 0xEF => 3,
+            0xF0 ..= 0xF7 => 4,
+            _ => 1,
+        };
+        let end = cmp::min(offset + length, self.source.len());
+        match str::from_utf8(&self.source[offset..end])
+        {
+            Ok(chr) => chr.chars().next().map(|chr| (Ok(chr), end - offset)),
+            // napacno zaporedje preskocimo v celoti
+            Err(e) => Some((Err(e), e.error_len().unwrap_or(end - offset))),
+        }
+     }
+
+     /** Vrne naslednji znak, ne da bi ga prebral, ali <code>None</code> ob koncu datoteke.
+     *
+     * Napacno zaporedje bajtov vrne kot <code>REPLACEMENT_CHARACTER</code>; kjer ga je treba lociti od veljavnega
+     * znaka U+FFFD, se uporabi <code>read_char</code>.
+     */
+     fn peek_char(&self) -> Option<char>
+     {
+        self.decode_char(self.offset).map(|(chr, _)| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+     }
+
+     /** Prebere naslednji znak in vrne <code>None</code> ob koncu datoteke (glej <code>peek_char</code>).  */
+     fn next_char(&mut self) -> Option<char>
      {
-        let mut byte = [0u8;1];
-        match self.reader.read(&mut byte) 
+        self.read_char().map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+     }
+
+     /** Prebere naslednji znak ali napacno zaporedje bajtov in vrne <code>None</code> ob koncu datoteke.
+     *
+     * Vrstico in stolpec naslednjega znaka doloci na enem mestu: konec vrstice je LF, CRLF ali samostojen CR,
+     * tabulator pa premakne stolpec do naslednjega tabulatorskega mesta. Napacno zaporedje zasede en stolpec.
+     */
+     fn read_char(&mut self) -> Option<Result<char, str::Utf8Error>>
+     {
+        let (chr, length) = self.decode_char(self.offset)?;
+        self.last = self.get_location();
+        self.offset += length;
+        match chr
         {
-            Ok(n) => 
+            // CR pred LF je del konca vrstice, ki ga zakljuci LF
+            Ok('\r') if self.source.get(self.offset) == Some(&b'\n') => self.column += 1,
+            Ok('\n') | Ok('\r') =>
             {
-                self.column += 1;
-                if n > 0 {Ok(Some(byte[0] as char))} else {Ok(None)}
+                self.line += 1;
+                self.column = 1;
             },
-            Err(e) => Err(e),
+            Ok('\t') => self.column = ((self.column - 1) / self.tab_width + 1) * self.tab_width + 1,
+            _ => self.column += 1,
         }
+        Some(chr)
      }
 
-     /** Move file cursor 1 step back*/
-     fn seek_back(&mut self) 
+     // mesto naslednjega neprebranega znaka
+     fn get_location(&self) -> Location
      {
-         self.reader.seek(SeekFrom::Current(-1)).unwrap();
-         self.column -= 1;
-      }
+        Location { line : self.line, column : self.column, offset : self.offset }
+     }
 
-     fn get_literal_position(&self, begin_column : u64, lenght : u64) -> Position
+     // polozaj od podanega mesta do vkljucno zadnjega prebranega znaka
+     fn get_position_from(&self, begin : Location) -> Position
      {
-        Position::new(&self.file_name, self.line,begin_column,self.line,begin_column + lenght -1)
+        Position::new(self.file_name, begin.line, begin.column, begin.offset, self.last.line, self.last.column, self.offset)
      }
 
-     pub fn get_next_symbol(&mut self) -> Result<Option<Symbol>, io::Error> 
+     // polozaj zadnjega prebranega znaka
+     fn get_last_char_position(&self) -> Position
      {
-         let mut state : ParserState = ParserState::InitialState;
-         let mut literal = String::new();
-         let mut literal_begin = self.column;
+        self.get_position_from(self.last)
+     }
 
-         loop 
-        {
-             let chr = match self.get_next_char() 
-            {
-                 Ok(Some(c)) =>
-                {
-                    Some(c)
-                },
-                 Ok(None) => None, 
-                 Err(e) => return Err(e),
-            };
+     /** Zabelezi leksikalno napako.  */
+     fn error_at_position(&self, msg : &str, position : &Position)
+     {
+         self.diagnostics.push(Diagnostic::error(msg, Some(position), ExitCode::LexicalAnalyzerIlegallChar));
+     }
+
+     /** Zabelezi leksikalno napako in vrne simbol <code>ERROR</code>, ki pokriva napacni del vhodne datoteke.
+     * @param msg Opis napake.
+     * @param literal Napacni del vhodne datoteke.
+     * @param begin Mesto, na katerem se napacni del zacne.
+     */
+     fn error_symbol(&self, msg : &str, literal : &str, begin : Location) -> Option<Symbol>
+     {
+         let position = self.get_position_from(begin);
+         self.error_at_position(msg, &position);
+         Symbol::new(Token::ERROR, literal, position)
+     }
 
-             match state 
+     /** Prebere preostanek napacnega stevila ali imena, da se leksikalna analiza nadaljuje za njim.  */
+     fn skip_word(&mut self, literal : &mut String)
+     {
+         while let Some(c) = self.peek_char()
+         {
+             if !is_word_char(c)
+             {
+                 break;
+             }
+             self.next_char();
+             literal.push(c);
+         }
+     }
+
+     /** Prebere znakovno konstanto, ki je koncni avtomat ne sprejme; zacetni narekovaj je ze prebran.
+     *
+     * Napacna ubezna zaporedja, kontrolni znaki in napacna zaporedja bajtov so zabelezeni kot napake in izpusceni,
+     * ostanek konstante pa je vrnjen kot simbol <code>STRINGCONST</code>. Nezakljucena konstanta je vrnjena kot
+     * simbol <code>ERROR</code>.
+     * @param begin Mesto zacetnega narekovaja.
+     */
+     fn scan_string(&mut self, begin : Location) -> Option<Symbol>
+     {
+         let mut lexeme = String::from("\"");
+         let mut value = String::new();
+         loop
+         {
+             let chr = match self.read_char()
              {
-                 ParserState::InitialState => 
+                 Some(Ok(chr)) => chr,
+                 Some(Err(_)) =>
                  {
-                     let chr = match chr 
-                    {
-                        Some(c) => c,
-                        None => return Ok(None), // Symbol::new(Token::EOF, self.get_literal_position(literal_begin,0))
-                    };
-                    match chr 
-                    {
-                        ' '  => literal_begin += 1,
-                        '\t' => literal_begin += 4,
-                        '\r' => { self.column += 1; literal_begin +=1;}
-                        '\n' =>{self.line +=1; self.column = 1; literal_begin =1;},
-                        '+' => return Ok(Symbol::new(Token::ADD, String::from("+"), self.get_literal_position(literal_begin,1))), 
-                        '-' => return Ok(Symbol::new(Token::SUB, String::from("-"), self.get_literal_position(literal_begin,1))),
-                        '*' => return Ok(Symbol::new(Token::MUL, String::from("*"), self.get_literal_position(literal_begin,1))),
-                        '/' => return Ok(Symbol::new(Token::DIV, String::from("/"), self.get_literal_position(literal_begin,1))),
-                        '%' => return Ok(Symbol::new(Token::MOD, String::from("%"), self.get_literal_position(literal_begin,1))),
-                        '!' => return Ok(Symbol::new(Token::NOT, String::from("!"), self.get_literal_position(literal_begin,1))),
-                        '&' => return Ok(Symbol::new(Token::AND, String::from("&"), self.get_literal_position(literal_begin,1))),
-                        '|' => return Ok(Symbol::new(Token::OR, String::from("|"), self.get_literal_position(literal_begin,1))),
-                        '=' => state = ParserState::EqualsState,
-                        '<' => state = ParserState::LessState,
-                        '>' => state = ParserState::GraterState,
-                        '#' => state = ParserState::CommentState,
-                        '\"' => state = ParserState::StringConstState,
-                        '(' =>  return Ok(Symbol::new(Token::LPARENT, String::from("("), self.get_literal_position(literal_begin,1))),
-                        ')' =>  return Ok(Symbol::new(Token::RPARENT, String::from(")"), self.get_literal_position(literal_begin,1))),
-                        '[' =>  return Ok(Symbol::new(Token::LBRACKET, String::from("["), self.get_literal_position(literal_begin,1))),
-                        ']' =>  return Ok(Symbol::new(Token::RBRACKET, String::from("]"), self.get_literal_position(literal_begin,1))),
-                        '{' =>  return Ok(Symbol::new(Token::LBRACE, String::from("{"), self.get_literal_position(literal_begin,1))),
-                        '}' =>  return Ok(Symbol::new(Token::RBRACE, String::from("}"), self.get_literal_position(literal_begin,1))),
-                        '.' =>  return Ok(Symbol::new(Token::DOT, String::from("."), self.get_literal_position(literal_begin,1))),
-                        ',' =>  return Ok(Symbol::new(Token::COMMA, String::from(","), self.get_literal_position(literal_begin,1))),
-                        ':' =>  return Ok(Symbol::new(Token::COLON, String::from(":"), self.get_literal_position(literal_begin,1))),
-                        ';' =>  return Ok(Symbol::new(Token::SEMIC, String::from(";"), self.get_literal_position(literal_begin,1))),
-                        c => 
-                        {
-                            if c.is_alphabetic() || c == '_' 
-                            {
-                                state = ParserState::IdentifierState;
-                                literal.push(c);
-                            }
-                            else if c.is_numeric() 
-                            {
-                                state = ParserState::IntConstState;
-                                literal.push(c);
-                            }
-                            else 
-                            {
-                                report::error_at_position(&format!("Lexical anayzer: Invalid character {} (ascii: {})",c,c as u8),
-                                                                        &self.get_literal_position(literal_begin,1),
-                                                                        ExitCode::LexicalAnalyzerIlegallChar);
-                            }
-                        }
-                    }
+                     lexeme.push(char::REPLACEMENT_CHARACTER);
+                     self.error_at_position("Invalid UTF-8 byte sequence in string", &self.get_last_char_position());
+                     continue;
                  },
-                 ParserState::EqualsState => 
+                 None =>
                  {
-                     match chr 
-                    {
-                        Some('=') => return Ok(Symbol::new(Token::EQU, String::from("=="), self.get_literal_position(literal_begin,2))),
-                        _ =>
-                        { 
-                            self.seek_back();  
-                            return Ok(Symbol::new(Token::ASSIGN, String::from("="), self.get_literal_position(literal_begin,1)));
-                        },
-                    }
+                     let position = self.get_position_from(begin);
+                     self.error_at_position("Error: End of file occured, but string not closed!", &position);
+                     return Symbol::new(Token::ERROR, &lexeme, position);
                  },
-                 ParserState::LessState =>
+             };
+             lexeme.push(chr);
+             match chr
+             {
+                 '\"' =>
                  {
-                     match chr 
-                     {
-                         Some('>') => return Ok(Symbol::new(Token::NEQ, String::from("<>"), self.get_literal_position(literal_begin,2))),
-                         Some('=') => return Ok(Symbol::new(Token::LEQ, String::from("<="), self.get_literal_position(literal_begin,2))),
-                        _ => 
-                        {
-                            self.seek_back();
-                            return Ok(Symbol::new(Token::LTH, String::from("<"), self.get_literal_position(literal_begin,1)));
-                        },
-                     }
+                     let position = self.get_position_from(begin);
+                     return Symbol::new_with_value(Token::STRINGCONST, &lexeme, LiteralValue::Str(value), position);
                  },
-                 ParserState::GraterState => 
+                 '\\' =>
                  {
-                     match chr 
+                     let escape_begin = self.last;
+                     let (escaped, length) = literal::decode_escape(self.get_text(MAX_ESCAPE_LENGTH));
+                     let end = self.offset + length;
+                     while self.offset < end
+                     {
+                         lexeme.extend(self.next_char());
+                     }
+                     match escaped
                      {
-                         Some('=') => return Ok(Symbol::new(Token::GEQ, String::from(">="), self.get_literal_position(literal_begin,2))), 
-                         _ => 
+                         Ok(chr) => value.push(chr),
+                         Err(msg) =>
                          {
-                             self.seek_back();
-                             return Ok(Symbol::new(Token::GTH, String::from(">"), self.get_literal_position(literal_begin,1)));
+                             let position = self.get_position_from(escape_begin);
+                             self.error_at_position(&msg, &position);
                          },
                      }
                  },
-                 ParserState::CommentState => 
+                 '\n' | '\t' | '\r' => value.push(chr),
+                 c if c.is_control() =>
+                 {
+                     self.error_at_position(&format!("Invalid character '{}' (U+{:04X}) in string", c.escape_default(), c as u32),
+                                            &self.get_last_char_position());
+                 },
+                 c => value.push(c),
+             }
+         }
+     }
+
+     /** Vrne veljavno besedilo UTF-8 od naslednjega neprebranega znaka, dolgo najvec <code>max_length</code> bajtov.  */
+     fn get_text(&self, max_length : usize) -> &str
+     {
+         let end = cmp::min(self.offset + max_length, self.source.len());
+         let bytes = &self.source[self.offset..end];
+         match str::from_utf8(bytes)
+         {
+             Ok(text) => text,
+             Err(e) => str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or(""),
+         }
+     }
+
+     /** Preskoci blocni komentar <code>#{ ... }#</code>, ki je lahko gnezden.
+     * @param begin Polozaj zacetnega <code>#{</code>, ki je ze prebran.
+     */
+     fn skip_block_comment(&mut self, begin : Position)
+     {
+         let mut depth = 1;
+         while depth > 0
+         {
+             match self.read_char()
+             {
+                 Some(Ok('#')) if self.peek_char() == Some('{') =>
+                 {
+                     self.next_char();
+                     depth += 1;
+                 },
+                 Some(Ok('}')) if self.peek_char() == Some('#') =>
+                 {
+                     self.next_char();
+                     depth -= 1;
+                 },
+                 Some(Err(_)) =>
+                 {
+                     self.error_at_position("Invalid UTF-8 byte sequence in comment", &self.get_last_char_position());
+                 },
+                 Some(Ok(_)) => {},
+                 None =>
+                 {
+                     self.error_at_position("Unterminated block comment: missing }#", &begin);
+                     return;
+                 },
+             }
+         }
+     }
+
+     // komentar # do konca vrstice; zacetni # je ze prebran
+     fn skip_line_comment(&mut self)
+     {
+         while self.peek_char().is_some_and(|c| c != '\n' && c != '\r')
+         {
+             match self.read_char()
+             {
+                 Some(Err(_)) => self.error_at_position("Invalid UTF-8 byte sequence in comment", &self.get_last_char_position()),
+                 Some(Ok(c)) if c.is_control() && c != '\t' =>
+                 {
+                     self.error_at_position(&format!("Invalid character '{}' (U+{:04X}) in comment", c.escape_default(), c as u32),
+                                            &self.get_last_char_position());
+                 },
+                 _ => {},
+             }
+         }
+     }
+
+     // v nacinu brez izgub zabelezi del izvorne kode od begin do zadnjega prebranega znaka
+     fn add_trivia(&mut self, kind : TriviaKind, begin : Location)
+     {
+         if !self.lossless
+         {
+             return;
+         }
+         let text = String::from_utf8_lossy(&self.source[begin.offset..self.offset]).into_owned();
+         let position = self.get_position_from(begin);
+         if let Some(last) = self.pending_trivia.last_mut()
+         {
+             // zaporedni presledki in CR LF tvorijo en del
+             let joined = match kind
+             {
+                 TriviaKind::Whitespace => true,
+                 TriviaKind::Newline => last.get_text() == "\r" && text == "\n",
+                 _ => false,
+             };
+             if joined && last.get_kind() == kind && last.get_position().get_end_offset() == begin.offset
+             {
+                 last.extend(&text, &position);
+                 return;
+             }
+         }
+         self.pending_trivia.push(Trivia::new(kind, text, position));
+     }
+
+     // prebere presledke in komentarje za simbolom do konca vrstice; dokumentacijski komentarji spadajo k naslednjemu simbolu
+     fn scan_trailing_trivia(&mut self)
+     {
+         loop
+         {
+             let begin = self.get_location();
+             match self.peek_char()
+             {
+                 Some(' ') | Some('\t') =>
                  {
-                     match chr 
+                     self.next_char();
+                     self.add_trivia(TriviaKind::Whitespace, begin);
+                 },
+                 Some('#') if !matches!(self.decode_char(self.offset + 1), Some((Ok('#'), _))) =>
+                 {
+                     self.next_char();
+                     if self.peek_char() == Some('{')
                      {
-                         Some('\r') => {},
-                         Some('\n') => 
-                        {
-                             state = ParserState::InitialState;
-                             self.column = 1;
-                             self.line += 1;
-                             literal_begin = 1;
-                        },
-                         Some(c) if (c as u8) < 32 || (c as u8) > 126 => report::error_at_position(&format!("Invalid character '{}' (ascii: {}) in comment\n",c, c as u8),
-                                                                                                                                            &self.get_literal_position(literal_begin,1),
-                                                                                                                                            ExitCode::LexicalAnalyzerIlegallChar),
-                        None => return Ok(None),
-                         _ => {},
+                         self.next_char();
+                         self.skip_block_comment(self.get_position_from(begin));
+                         self.add_trivia(TriviaKind::BlockComment, begin);
                      }
-                 }
-                 ParserState::IdentifierState =>
-                 {
-                     match chr 
+                     else
                      {
-                         Some(c) if c.is_alphabetic() || c.is_digit(10) || c == '_' => literal.push(c),
-                         _ => 
-                         {
-                            self.seek_back();
-                            return Ok(self.get_symbol_type(literal,literal_begin));
-                         },
+                         self.skip_line_comment();
+                         self.add_trivia(TriviaKind::Comment, begin);
                      }
                  },
-                 ParserState::IntConstState => 
+                 _ => return,
+             }
+         }
+     }
+
+     // vrstico dokumentacijskega komentarja (brez ## in prvega presledka) doda komentarju naslednjega simbola
+     fn add_doc_line(&mut self, line : &str)
+     {
+         let line = line.strip_prefix(' ').unwrap_or(line);
+         match self.pending_doc
+         {
+             Some(ref mut doc) =>
+             {
+                 doc.push('\n');
+                 doc.push_str(line);
+             },
+             None => self.pending_doc = Some(line.to_string()),
+         }
+     }
+
+     /** Vrne naslednji osnovni simbol.
+     *
+     * Leksikalne napake so zabelezene v zbiralniku obvestil, napacni del vhodne datoteke pa je vrnjen
+     * kot simbol <code>ERROR</code>. Dokumentacijski komentarji (<code>##</code>) pred simbolom so mu pripeti
+     * (glej <code>Symbol::get_doc</code>).
+     * @return Naslednji osnovni simbol ali <code>None</code> ob koncu datoteke.
+     */
+     pub fn get_next_symbol(&mut self) -> Result<Option<Symbol>, LexError> 
+     {
+         let symbol = match self.lookahead.pop_front()
+         {
+             Some(symbol) => Some(symbol),
+             None => self.read_symbol()?,
+         };
+         if let (Some(symbol), Some(recorded_symbols)) = (symbol.as_ref(), self.recorded_symbols.as_mut())
+         {
+             recorded_symbols.push(symbol.clone());
+         }
+         Ok(symbol)
+     }
+
+     /** Vrne simbol, ki bo vrnjen za <code>k</code> klici <code>get_next_symbol</code>, ne da bi ga vrnil.
+     *
+     * <code>peek(0)</code> pogleda naslednji simbol. Pogledani simboli se hranijo, dokler jih ne vrne
+     * <code>get_next_symbol</code>; leksikalne napake v njih so zabelezene ze ob prvem pogledu.
+     * @return Simbol ali <code>None</code>, ce se datoteka konca prej.
+     */
+     pub fn peek(&mut self, k : usize) -> Result<Option<&Symbol>, LexError>
+     {
+         while self.lookahead.len() <= k
+         {
+             match self.read_symbol()?
+             {
+                 Some(symbol) => self.lookahead.push_back(symbol),
+                 None => return Ok(None),
+             }
+         }
+         Ok(self.lookahead.get(k))
+     }
+
+     // prebere naslednji simbol iz vhodne datoteke in mu pripne komentarje
+     fn read_symbol(&mut self) -> Result<Option<Symbol>, LexError>
+     {
+         let mut symbol = self.scan_symbol();
+         if let Some(symbol) = symbol.as_mut()
+         {
+             symbol.set_doc(self.pending_doc.take());
+             if self.lossless
+             {
+                 let leading_trivia = mem::take(&mut self.pending_trivia);
+                 self.scan_trailing_trivia();
+                 let trailing_trivia = mem::take(&mut self.pending_trivia);
+                 symbol.set_trivia(leading_trivia, trailing_trivia);
+             }
+         }
+         Ok(symbol)
+     }
+
+     // preskoci presledke in komentarje ter prebere naslednji simbol
+     fn scan_symbol(&mut self) -> Option<Symbol>
+     {
+         loop 
+         {
+             // mesto prvega znaka simbola
+             let begin = self.get_location();
+             // ob koncu datoteke ni vec simbolov
+             match self.peek_char()?
+             {
+                 ' ' | '\t' =>
+                 {
+                     self.next_char();
+                     self.add_trivia(TriviaKind::Whitespace, begin);
+                 },
+                 '\r' | '\n' =>
+                 {
+                     self.next_char();
+                     self.add_trivia(TriviaKind::Newline, begin);
+                 },
+                 '#' =>
                  {
-                     match chr 
+                     self.next_char();
+                     match self.peek_char()
                      {
-                         Some(c) if c.is_digit(10) => literal.push(c),
-                         Some('.') => 
+                         Some('{') =>
                          {
-                             state = ParserState::RealConstState;
-                             literal.push('.');
-                             match try!(self.get_next_char())
-                             {
-                                 Some(c) if c.is_digit(10) => literal.push(c),
-                                 _ => report::error_at_position("Error while parsing REALCONST. Dot (.) must be followed by at least one digit!",
-                                                                     &self.get_literal_position(literal_begin, literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar),
-                             }
+                             self.next_char();
+                             self.skip_block_comment(self.get_position_from(begin));
+                             self.add_trivia(TriviaKind::BlockComment, begin);
                          },
-                         Some(c) if c.is_alphabetic() => 
+                         Some('#') =>
                          {
-                             report::error_at_position(&format!("Error while parsing INTCONST: illegal character {}!",c),
-                                                                     &self.get_literal_position(literal_begin,literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar);
+                             self.next_char();
+                             self.scan_doc_comment();
+                             self.add_trivia(TriviaKind::DocComment, begin);
                          },
-                         None => 
+                         _ =>
                          {
-                             let len = literal.len() as u64;
-                             return Ok(Symbol::new(Token::INTCONST, literal, self.get_literal_position(literal_begin, len)));
-                         },
-                         _ =>  
-                         {
-                             let len = literal.len() as u64;
-                             self.seek_back();
-                             return Ok(Symbol::new(Token::INTCONST, literal, self.get_literal_position(literal_begin, len)));
+                             self.skip_line_comment();
+                             self.add_trivia(TriviaKind::Comment, begin);
                          },
                      }
                  },
-                 ParserState::RealConstState => 
-                 {
-                    match chr 
-                    {
-                        Some(c) if c.is_digit(10) => literal.push(c),
-                        Some(c) if c == 'e' || c =='E' => 
-                        {
-                            state = ParserState::RealExcpConstState;
-                            literal.push(c);
-                            match try!(self.get_next_char()) 
-                            {
-                                 Some(c) if c == '+' || c == '-' =>
-                                 {
-                                      literal.push(c);
-                                      match try!(self.get_next_char()) 
-                                      {
-                                          Some(c) if c.is_digit(10) => literal.push(c),
-                                          _ =>  report::error_at_position("Error while parsing REALCONST. Illegal character!",
-                                                                     &self.get_literal_position(literal_begin, literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar),
-                                      }
-                                 },
-                                 Some(c) if c.is_digit(10) => literal.push(c),
-                                 _=> report::error_at_position("Error while parsing REALCONST. Illegal character!",
-                                                                     &self.get_literal_position(literal_begin, literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar),
-                            }
-                        }
-                        Some(c) if c.is_alphabetic() => 
-                        {
-                             report::error_at_position(&format!("Error while parsing REALCONST: illegal character {}!",c),
-                                                                     &self.get_literal_position(literal_begin,literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar);
-                        },
-                        None => 
-                        {
-                            let len = literal.len() as u64;
-                             return Ok(Symbol::new(Token::REALCONST, literal, self.get_literal_position(literal_begin, len)));
-                        },
-                        _ => 
-                        {
-                             let len = literal.len() as u64;
-                             self.seek_back();
-                             return Ok(Symbol::new(Token::REALCONST, literal, self.get_literal_position(literal_begin, len)));
-                        },
-                    }
-                 },
-                 ParserState::RealExcpConstState => 
+                 _ => return self.scan_table_symbol(begin),
+             }
+         }
+     }
+
+     /** S koncnim avtomatom prebere najdaljse besedilo, ki ustreza kateremu od vzorcev v <code>TOKEN_TABLE</code>.
+     *
+     * Znaki, ki jih je avtomat prebral za zadnjim sprejetim simbolom, se vrnejo v vhodno datoteko.
+     * Stevilo je napacno, ce je avtomat za njim bral naprej (npr. <code>1.</code> ali <code>0x</code>) ali mu sledi
+     * crka, stevka ali <code>_</code>; napacna znakovna konstanta se prebere s <code>scan_string</code>.
+     * Ce avtomat ne sprejme nobenega simbola, je prvi znak napacen.
+     */
+     fn scan_table_symbol(&mut self, begin : Location) -> Option<Symbol>
+     {
+         let dfa = self.dfa.clone();
+         let mut state = dfa.get_start_state();
+         // simbol, koncno stanje avtomata in stanje analizatorja za simbolom
+         let mut accepted : Option<(Token, usize, (Location, Location))> = None;
+         while let Some((Ok(c), _)) = self.decode_char(self.offset)
+         {
+             state = match dfa.get_next_state(state, c)
+             {
+                 Some(state) => state,
+                 None => break,
+             };
+             self.next_char();
+             if let Some(token) = dfa.get_token(state)
+             {
+                 accepted = Some((token, state, (self.get_location(), self.last)));
+             }
+         }
+
+         // stanje analizatorja za zadnjim znakom, ki ga je prebral avtomat
+         let furthest = (self.get_location(), self.last);
+
+         match accepted
+         {
+             Some((token, state, cursor)) =>
+             {
+                 self.set_cursor(cursor);
+                 // stevilo, za katerim je avtomat bral naprej ali mu sledi crka, je napacno (npr. 1., 0x ali 12ab)
+                 if token == Token::INTCONST || token == Token::REALCONST
                  {
-                    match chr {
-                        Some(c) if c.is_digit(10) => literal.push(c),
-                        Some(c) if c.is_alphabetic() => 
-                        {
-                            report::error_at_position(&format!("Error while parsing REALEXCPCONST: illegal character {}!",c),
-                                                                     &self.get_literal_position(literal_begin,literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar);
-                        }
-                        None => 
-                        {
-                             let len = literal.len() as u64;
-                             return Ok(Symbol::new(Token::REALCONST, literal, self.get_literal_position(literal_begin, len)));
-                        },
-                        _ => 
-                        {
-                             let len = literal.len() as u64;
-                             self.seek_back();
-                             return Ok(Symbol::new(Token::REALCONST, literal, self.get_literal_position(literal_begin, len)));
-                        },
-                    }
-                 },
-                 ParserState::StringConstState => 
-                 {  
-                     match chr 
+                     let msg = match self.peek_char()
                      {
-                         Some(c) if c.is_alphabetic() || c.is_digit(10) || c.is_whitespace() => literal.push(c),
-                         Some('\\') => {state = ParserState::StringEscapeState; },
-                         Some('\"') => 
-                         {
-                             let len = literal.len() as u64;
-                             return Ok(Symbol::new(Token::STRINGCONST, literal, self.get_literal_position(literal_begin, len)));
-                         },
-                         None =>  report::error_at_position(&format!("Error: End of file occured, but string not closed!"),
-                                                                     &self.get_literal_position(literal_begin,literal.len() as u64),
-                                                                     ExitCode::LexicalAnalyzerIlegallChar),
-                         _ =>  {},
-                     }
-                 },
-                 ParserState::StringEscapeState => 
-                 {
-                     match chr
+                         _ if furthest.0.offset > cursor.0.offset => Some(String::from("Incomplete numeric constant")),
+                         Some(c) if is_word_char(c) => Some(format!("Invalid character '{}' in numeric constant", c)),
+                         _ => None,
+                     };
+                     if let Some(msg) = msg
                      {
-                         Some('\\') => { literal.push('\\'); state = ParserState::StringConstState; },
-                         Some('\"') => { literal.push('\"'); state = ParserState::StringConstState; },
-                         Some('\'') => { literal.push('\''); state = ParserState::StringConstState; },
-                         _ => report::error_at_position("Error: String escape!",
-                                                                        &self.get_literal_position(literal_begin,literal.len() as u64),
-                                                                        ExitCode::LexicalAnalyzerIlegallChar),
+                         self.set_cursor(furthest);
+                         let mut literal = String::from_utf8_lossy(&self.source[begin.offset..self.offset]).into_owned();
+                         self.skip_word(&mut literal);
+                         return self.error_symbol(&format!("{} {}", msg, literal), &literal, begin);
                      }
+                 }
+                 self.get_table_symbol(token, dfa.get_lexeme(state), begin)
+             },
+             // znakovno konstanto, ki je avtomat ne sprejme, prebere scan_string in zabelezi njene napake
+             None if self.source[begin.offset] == b'"' =>
+             {
+                 self.set_cursor((begin, self.last));
+                 self.next_char();
+                 self.scan_string(begin)
+             },
+             None =>
+             {
+                 self.set_cursor((begin, self.last));
+                 match self.read_char()?
+                 {
+                     Ok(c) => self.error_symbol(&format!("Invalid character '{}' (U+{:04X})", c.escape_default(), c as u32), &c.to_string(), begin),
+                     Err(_) => self.error_symbol("Invalid UTF-8 byte sequence", &char::REPLACEMENT_CHARACTER.to_string(), begin),
+                 }
+             },
+         }
+     }
+
+     // postavi analizator na podano mesto naslednjega znaka in mesto zadnjega prebranega znaka
+     fn set_cursor(&mut self, cursor : (Location, Location))
+     {
+         let (next, last) = cursor;
+         self.offset = next.offset;
+         self.line = next.line;
+         self.column = next.column;
+         self.last = last;
+     }
+
+     // prebere dokumentacijski komentar do konca vrstice; zacetni ## je ze prebran
+     fn scan_doc_comment(&mut self)
+     {
+         let mut text = String::new();
+         while self.peek_char().is_some_and(|c| c != '\n' && c != '\r')
+         {
+             match self.read_char()
+             {
+                 Some(Err(_)) => self.error_at_position("Invalid UTF-8 byte sequence in comment", &self.get_last_char_position()),
+                 Some(Ok(c)) if c.is_control() && c != '\t' =>
+                 {
+                     self.error_at_position(&format!("Invalid character '{}' (U+{:04X}) in comment", c.escape_default(), c as u32),
+                                            &self.get_last_char_position());
                  },
+                 Some(Ok(c)) => text.push(c),
+                 None => {},
              }
-
          }
+         self.add_doc_line(&text);
      }
 
 }
 
 
+// znak, ki lahko nadaljuje ime ali stevilo
+fn is_word_char(chr : char) -> bool
+{
+    chr.is_alphabetic() || chr.is_ascii_digit() || chr == '_'
+}
+
+/** Zaporedje osnovnih simbolov do konca datoteke; enako kot zaporedni klici <code>get_next_symbol</code>. */
+impl Iterator for LexicalAnalyzer
+{
+    type Item = Result<Symbol, LexError>;
+
+    fn next(&mut self) -> Option<Result<Symbol, LexError>>
+    {
+        self.get_next_symbol().transpose()
+    }
+}
@@ -3,34 +3,75 @@ use std::fmt::Display;
 use std::io::Write;
 use std::fmt::Formatter;
 use std::fmt::Error;
-use std::rc::Rc;
+use interner::Name;
+use std::sync::Arc;
 //use std::fmt::Error;
 
 
+/** Polozaj dela izvorne kode.
+ *
+ * Vrstice in stolpci se stejejo od 1; stolpec tabulatorja je odvisen od sirine tabulatorja leksikalnega analizatorja.
+ * Konec polozaja je zadnji znak, ki spada k polozaju. Odmika sta stevili bajtov od zacetka datoteke do prvega
+ * bajta polozaja in do prvega bajta za njim.
+ */
 #[derive(Debug, Clone)]
 pub struct Position {
-    filename: Rc<String>,
+    filename: Name,
     begin_line : u64,
     begin_column : u64,
+    begin_offset : usize,
     end_line : u64,
     end_column : u64,
+    end_offset : usize,
 }
 
 impl Position {
-   pub fn new(filename : &Rc<String>, begin_line : u64, begin_column : u64, end_line : u64, end_column : u64 ) -> Position {
+   pub fn new(filename : Name, begin_line : u64, begin_column : u64, begin_offset : usize, end_line : u64, end_column : u64, end_offset : usize) -> Position {
       Position{ 
-        filename : filename.clone(),
+        filename,
         begin_line,
         begin_column,
+        begin_offset,
         end_line,
         end_column,
+        end_offset,
         }
    }
 
    pub fn get_description(&self) -> String 
    {
        // "line " + begLine + " column: " + begColumn + ", filename: " + filename;
-       format!("filename: {}, line {} column {}",*self.filename,self.begin_line,self.begin_column)
+       format!("filename: {}, line {} column {}",self.filename,self.begin_line,self.begin_column)
+   }
+
+   pub fn get_filename(&self) -> Arc<str>
+   {
+       self.filename.as_str()
+   }
+
+   /** Vrne oznako imena datoteke v tabeli imen. */
+   pub fn get_filename_id(&self) -> Name
+   {
+       self.filename
+   }
+
+   pub fn get_begin_line(&self) -> u64 { self.begin_line }
+   pub fn get_begin_column(&self) -> u64 { self.begin_column }
+   pub fn get_end_line(&self) -> u64 { self.end_line }
+   pub fn get_end_column(&self) -> u64 { self.end_column }
+   pub fn get_begin_offset(&self) -> usize { self.begin_offset }
+   pub fn get_end_offset(&self) -> usize { self.end_offset }
+
+   /** Premakne polozaj za podano stevilo vrstic in bajtov; stolpci ostanejo enaki.
+   * @param line_delta Sprememba stevilk vrstic.
+   * @param offset_delta Sprememba odmikov.
+   */
+   pub fn shift(&mut self, line_delta : i64, offset_delta : isize)
+   {
+       self.begin_line = (self.begin_line as i64 + line_delta) as u64;
+       self.end_line = (self.end_line as i64 + line_delta) as u64;
+       self.begin_offset = (self.begin_offset as isize + offset_delta) as usize;
+       self.end_offset = (self.end_offset as isize + offset_delta) as usize;
    }
 
    pub fn set_min(&mut self, position : &Position) 
@@ -39,6 +80,7 @@ impl Position {
        {
            self.begin_line = position.begin_line;
            self.begin_column = position.begin_column;
+           self.begin_offset = position.begin_offset;
        }
    }
 
@@ -48,6 +90,7 @@ impl Position {
        {
            self.end_line = position.end_line;
            self.end_column = position.end_column;
+           self.end_offset = position.end_offset;
        }
    }
 }
@@ -57,7 +100,7 @@ impl Display for Position
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error>
     {
         // return "[" + filename + ":" + begLine + "." + begColumn + "-" + endLine + "." + endColumn + "]";
-        write!(f, "[{}: {}.{}-{}.{}]", *self.filename,self.begin_line,self.begin_column,self.end_line,self.end_column)
+        write!(f, "[{}: {}.{}-{}.{}]", self.filename,self.begin_line,self.begin_column,self.end_line,self.end_column)
     }
 }
 
@@ -67,6 +110,6 @@ impl XMLable for Position
     {
         // xml.println("<position filename=\"" + filename + "\" begLine=\"" + begLine + "\" begColumn=\"" + begColumn + "\"
         // endLine=\"" + endLine + "\" endColumn=\"" + endColumn + "\"/>");
-        writeln!(xml, "<position filename=\"{}\" begLine=\"{}\" begColumn=\"{}\" endLine=\"{}\" endColumn=\"{}\"/> ", *self.filename, self.begin_line,self.begin_column,self.end_line,self.end_column).unwrap();
+        writeln!(xml, "<position filename=\"{}\" begLine=\"{}\" begColumn=\"{}\" endLine=\"{}\" endColumn=\"{}\"/> ", self.filename, self.begin_line,self.begin_column,self.end_line,self.end_column).unwrap();
     }
 }
\ No newline at end of file
@@ -1,40 +1,49 @@
-use std;
-use lexanal::LexicalAnalyzer;
 use xml::ProteusXmlCreator;
 use std::str::FromStr;
-use xml::XMLable;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use lexanal::dump::{self, DumpFormat};
+use compiler::{self, CompileOptions};
+use driver::{self, Options};
 
-pub fn run(program_name : String)
+pub fn run(options : &Options) -> i32
 {
-    std::env::set_var("PROTEUSXSL",std::env::current_dir().unwrap().join("xsl\\"));
-    let mut lexical_analyser = match LexicalAnalyzer::new(program_name) 
+    let result = match compiler::compile(&options.program_name, &CompileOptions::from(options))
     {
-        Ok(lexical_analyser) => lexical_analyser,
+        Ok(result) => result,
         Err(e) =>
         {
-             println!("{}",e);
-             std::process::exit(-1);
+             eprintln!(":-( {}",e);
+             return driver::EXIT_IO_ERROR;
         }
     };
 
-    let mut lexanal_xml_creator : ProteusXmlCreator = match ProteusXmlCreator::open(String::from_str("lexanal").unwrap()) {
-        Ok(xml_creator) => xml_creator,
+    // XML izpis potrebuje glavo in zakljucno oznako, ostale oblike so le zaporedje vrstic
+    let output : io::Result<Box<Write>> = match options.format
+    {
+        DumpFormat::Xml => ProteusXmlCreator::open_in_dir(String::from_str("lexanal").unwrap(), &options.output_dir, options.get_xsl_dir())
+                               .map(|xml_creator| Box::new(xml_creator) as Box<Write>),
+        _ => File::create(options.get_output_path()).map(|file| Box::new(BufWriter::new(file)) as Box<Write>),
+    };
+    let mut output = match output {
+        Ok(output) => output,
         Err(e) =>
         {
-             println!("{}",e);
-             std::process::exit(-2);
+             eprintln!(":-( {}",e);
+             return driver::EXIT_IO_ERROR;
         },
     };
-    loop 
+    for symbol in &result.symbols
+    {
+        if let Err(e) = dump::write_symbol(symbol, options.format, &mut output)
+        {
+            eprintln!(":-( {}", e);
+            return driver::EXIT_IO_ERROR;
+        }
+    }
+    if options.is_verbose()
     {
-       match lexical_analyser.get_next_symbol() 
-       {
-           Ok(Some(symbol)) => symbol.to_xml(&mut lexanal_xml_creator),
-           Ok(None) => break,
-           Err(e) => 
-           {
-               println!("An error occured while lexical analysis: {}",e);
-           }
-       }
+        eprintln!("lexanal: {} symbols", result.symbols.len());
     }
-}
\ No newline at end of file
+    driver::emit_diagnostics(&result, options)
+}
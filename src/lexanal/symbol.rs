@@ -1,29 +1,101 @@
+use interner::Name;
 use lexanal::position::Position;
+use lexanal::trivia::Trivia;
 use xml::XMLable;
 use std::io::Write;
 //use synanal::syntoken::SyntaxToken;
 use std::fmt::Display;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Debug)]
+/** Vrednost konstante, kot jo izracuna leksikalni analizator. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue
+{
+    Int(i64),
+    Real(f64),
+    Bool(bool),
+    // niz, v katerem so ubezna zaporedja ze razresena
+    Str(String),
+}
+
+// besedilo simbola; identifikatorji in rezervirane besede so v tabeli imen, konstante in napacni simboli pa ne
+#[derive(Debug, Clone)]
+enum Lexeme
+{
+    // oznaka v tabeli imen in niz iz tabele imen, ki se bere brez zaklepanja
+    Interned(Name, Arc<str>),
+    Owned(String),
+}
+
+#[derive(Debug, Clone)]
 pub struct Symbol 
 {
     token : Token,
-    pub lexeme : String,
+    // besedilo simbola, kot je zapisano v izvorni kodi (pri nizih skupaj z narekovaji in ubeznimi zaporedji)
+    lexeme : Lexeme,
+    // vrednost konstante; None pri simbolih, ki niso konstante
+    value : Option<LiteralValue>,
+    // dokumentacijski komentar (##) pred simbolom
+    doc : Option<String>,
+    // presledki, konci vrstic in komentarji pred simbolom in za njim (le v nacinu brez izgub)
+    leading_trivia : Vec<Trivia>,
+    trailing_trivia : Vec<Trivia>,
     position : Position,
 } 
 
 impl Symbol 
 {
-    pub fn new(token : Token, lexeme : String, position : Position)  -> Option<Symbol>
+    /** Ustvari simbol; besedilo identifikatorja ali rezervirane besede se shrani v tabelo imen, besedilo konstante ali napacnega simbola pa ne.
+    * @param token Vrsta simbola.
+    * @param lexeme Besedilo simbola.
+    * @param position Polozaj simbola.
+    */
+    pub fn new(token : Token, lexeme : &str, position : Position)  -> Option<Symbol>
+    {
+        let lexeme = match token
+        {
+            Token::INTCONST | Token::REALCONST | Token::BOOLCONST | Token::STRINGCONST | Token::ERROR => Lexeme::Owned(lexeme.to_string()),
+            _ =>
+            {
+                let (name, text) = Name::new_with_text(lexeme);
+                Lexeme::Interned(name, text)
+            },
+        };
+        Self::new_with_lexeme(token, lexeme, position)
+    }
+
+    /** Ustvari simbol, katerega besedilo je ze v tabeli imen.
+    * @param token Vrsta simbola.
+    * @param lexeme Oznaka besedila simbola v tabeli imen.
+    * @param text Besedilo simbola, kot ga hrani tabela imen (glej <code>Name::new_with_text</code>).
+    * @param position Polozaj simbola.
+    */
+    pub fn new_with_name(token : Token, lexeme : Name, text : Arc<str>, position : Position) -> Option<Symbol>
+    {
+        Self::new_with_lexeme(token, Lexeme::Interned(lexeme, text), position)
+    }
+
+    fn new_with_lexeme(token : Token, lexeme : Lexeme, position : Position) -> Option<Symbol>
     {
         match token 
         {
             Token::EOF => None,
-            _ => Some(Symbol { token, lexeme,  position }),
+            _ => Some(Symbol { token, lexeme, value : None, doc : None, leading_trivia : Vec::new(), trailing_trivia : Vec::new(), position }),
         }
     }
 
+    /** Ustvari konstanto z ze izracunano vrednostjo.
+    * @param token Vrsta konstante.
+    * @param lexeme Besedilo konstante, kot je zapisano v izvorni kodi.
+    * @param value Vrednost konstante.
+    * @param position Polozaj konstante (pri nizih vkljucno z narekovaji).
+    */
+    pub fn new_with_value(token : Token, lexeme : &str, value : LiteralValue, position : Position) -> Option<Symbol>
+    {
+        Some(Symbol { token, lexeme : Lexeme::Owned(lexeme.to_string()), value : Some(value), doc : None, leading_trivia : Vec::new(), trailing_trivia : Vec::new(), position })
+    }
+
     pub fn get_token(&self) -> Token
     {
         self.token
@@ -149,7 +221,92 @@ impl Symbol
 
     pub fn get_ref_lexeme(&self) -> &str
     {
-        self.lexeme.as_str()
+        match self.lexeme
+        {
+            Lexeme::Interned(_, ref text) => text,
+            Lexeme::Owned(ref text) => text,
+        }
+    }
+
+    /** Vrne oznako besedila simbola v tabeli imen (enaka identifikatorja imata enako oznako) ali <code>None</code>, ce je simbol konstanta ali napaka.  */
+    pub fn get_name(&self) -> Option<Name>
+    {
+        match self.lexeme
+        {
+            Lexeme::Interned(name, _) => Some(name),
+            Lexeme::Owned(_) => None,
+        }
+    }
+
+    /** Vrne vrednost konstante ali <code>None</code>, ce simbol ni konstanta.  */
+    pub fn get_value(&self) -> Option<&LiteralValue>
+    {
+        self.value.as_ref()
+    }
+
+    /** Vrne vrednost znakovne konstante ali <code>None</code>, ce simbol ni znakovna konstanta.  */
+    pub fn get_string_value(&self) -> Option<&str>
+    {
+        match self.value
+        {
+            Some(LiteralValue::Str(ref value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /** Vrne dokumentacijski komentar, ki je v izvorni kodi zapisan neposredno pred simbolom.  */
+    pub fn get_doc(&self) -> Option<&str>
+    {
+        self.doc.as_deref()
+    }
+
+    pub fn set_doc(&mut self, doc : Option<String>)
+    {
+        self.doc = doc;
+    }
+
+    /** Vrne dele izvorne kode med prejsnjim simbolom in tem simbolom, ki niso sledeci deli prejsnjega simbola.  */
+    pub fn get_leading_trivia(&self) -> &[Trivia]
+    {
+        &self.leading_trivia
+    }
+
+    /** Vrne presledke in komentarje za simbolom do konca njegove vrstice (brez konca vrstice).  */
+    pub fn get_trailing_trivia(&self) -> &[Trivia]
+    {
+        &self.trailing_trivia
+    }
+
+    pub fn set_trivia(&mut self, leading_trivia : Vec<Trivia>, trailing_trivia : Vec<Trivia>)
+    {
+        self.leading_trivia = leading_trivia;
+        self.trailing_trivia = trailing_trivia;
+    }
+
+    /** Vrne besedilo simbola skupaj z vodilnimi in sledecimi deli, kot je zapisano v izvorni kodi.  */
+    pub fn get_full_text(&self) -> String
+    {
+        let mut text = String::new();
+        for trivia in &self.leading_trivia
+        {
+            text.push_str(trivia.get_text());
+        }
+        text.push_str(self.get_ref_lexeme());
+        for trivia in &self.trailing_trivia
+        {
+            text.push_str(trivia.get_text());
+        }
+        text
+    }
+
+    /** Premakne simbol in njegove vodilne in sledece dele za podano stevilo vrstic in bajtov (glej <code>Position::shift</code>).  */
+    pub fn shift(&mut self, line_delta : i64, offset_delta : isize)
+    {
+        self.position.shift(line_delta, offset_delta);
+        for trivia in self.leading_trivia.iter_mut().chain(self.trailing_trivia.iter_mut())
+        {
+            trivia.shift(line_delta, offset_delta);
+        }
     }
 
     pub fn get_position(&self) -> &Position 
@@ -163,7 +320,7 @@ impl Symbol
 	 * @param lexeme Znakovna predstavitev osnovnega simbola.
 	 * @return Predstavitev osnovnega simbola za izpis v XML datoteki.
 */
-fn str_to_xml(lexeme : &str) -> String 
+pub fn str_to_xml(lexeme : &str) -> String 
 {
     let mut lex = String::new(); 
     for c in lexeme.chars()
@@ -175,6 +332,10 @@ fn str_to_xml(lexeme : &str) -> String
             '&' => lex.push_str("&#38;"),
             '<' => lex.push_str("&#60;"),
             '>' => lex.push_str("&#62;"),
+            // znaki v vecvrsticnih nizih
+            '\t' => lex.push_str("&#9;"),
+            '\n' => lex.push_str("&#10;"),
+            '\r' => lex.push_str("&#13;"),
              chr => lex.push(chr),
         }
     }
@@ -190,31 +351,31 @@ impl XMLable for Symbol
         {
             Token::IDENTIFIER => 
             {
-                writeln!(xml, "<symbol token=\"IDENTIFIER\"  lexeme=\"{}\">", str_to_xml(&self.lexeme)).unwrap(); 
+                writeln!(xml, "<symbol token=\"IDENTIFIER\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap(); 
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
             Token::INTCONST => 
             {
-                writeln!(xml, "<symbol token=\"INTCONST\"  lexeme=\"{}\">", str_to_xml(&self.lexeme)).unwrap(); 
+                writeln!(xml, "<symbol token=\"INTCONST\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap(); 
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
             Token::REALCONST => 
             {
-                writeln!(xml, "<symbol token=\"REALCONST\"  lexeme=\"{}\">", str_to_xml(&self.lexeme)).unwrap(); 
+                writeln!(xml, "<symbol token=\"REALCONST\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap(); 
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
             Token::BOOLCONST => 
             {
-                writeln!(xml, "<symbol token=\"BOOLCONST\"  lexeme=\"{}\">", str_to_xml(&self.lexeme)).unwrap(); 
+                writeln!(xml, "<symbol token=\"BOOLCONST\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap(); 
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
             Token::STRINGCONST => 
             {
-                writeln!(xml, "<symbol token=\"STRINGCONST\"  lexeme=\"{}\">", str_to_xml(&self.lexeme)).unwrap(); 
+                writeln!(xml, "<symbol token=\"STRINGCONST\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap(); 
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
@@ -422,6 +583,12 @@ impl XMLable for Symbol
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
+            Token::IMPORT => 
+            {
+                writeln!(xml, "<symbol token=\"IMPORT\">").unwrap(); 
+                self.position.to_xml(xml);
+                writeln!(xml,"</symbol>").unwrap();
+            },
             Token::REC => 
             {
                 writeln!(xml, "<symbol token=\"REC\">").unwrap(); 
@@ -458,6 +625,12 @@ impl XMLable for Symbol
                 self.position.to_xml(xml);
                 writeln!(xml,"</symbol>").unwrap();
             },
+            Token::ERROR =>
+            {
+                writeln!(xml, "<symbol token=\"ERROR\"  lexeme=\"{}\">", str_to_xml(self.get_ref_lexeme())).unwrap();
+                self.position.to_xml(xml);
+                writeln!(xml,"</symbol>").unwrap();
+            },
             Token::EOF => {},
         }
     }
@@ -469,7 +642,7 @@ impl Display for Symbol
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result 
     {
-        write!(f, "{}", self.lexeme)
+        write!(f, "{}", self.get_ref_lexeme())
     }
 }
 
@@ -532,6 +705,7 @@ pub enum Token
     FOR,
     FUN,
     IF,
+    IMPORT,
     REC,
     THEN,
     TYP,
@@ -539,6 +713,69 @@ pub enum Token
     WHERE, 
     WHILE,
 
+    // napacen del vhodne datoteke, o katerem je leksikalni analizator ze zabelezil napako
+    ERROR,
+
     //End of file
     EOF,
-}
\ No newline at end of file
+}
+
+/** Opis vrste osnovnega simbola za sporocila o napakah (npr. <code>identifier</code> ali <code>`then`</code>). */
+impl Display for Token
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let lexeme = match *self
+        {
+            Token::IDENTIFIER => return write!(f, "identifier"),
+            Token::INTCONST => return write!(f, "integer constant"),
+            Token::REALCONST => return write!(f, "real constant"),
+            Token::BOOLCONST => return write!(f, "boolean constant"),
+            Token::STRINGCONST => return write!(f, "string constant"),
+            Token::ERROR => return write!(f, "invalid token"),
+            Token::EOF => return write!(f, "end of file"),
+            Token::INT => "int",
+            Token::REAL => "real",
+            Token::BOOL => "bool",
+            Token::STRING => "string",
+            Token::ADD => "+",
+            Token::SUB => "-",
+            Token::MUL => "*",
+            Token::DIV => "/",
+            Token::MOD => "%",
+            Token::NOT => "!",
+            Token::AND => "&",
+            Token::OR => "|",
+            Token::EQU => "==",
+            Token::NEQ => "<>",
+            Token::LTH => "<",
+            Token::GTH => ">",
+            Token::LEQ => "<=",
+            Token::GEQ => ">=",
+            Token::ASSIGN => "=",
+            Token::LPARENT => "(",
+            Token::RPARENT => ")",
+            Token::LBRACKET => "[",
+            Token::RBRACKET => "]",
+            Token::LBRACE => "{",
+            Token::RBRACE => "}",
+            Token::DOT => ".",
+            Token::COMMA => ",",
+            Token::COLON => ":",
+            Token::SEMIC => ";",
+            Token::ARR => "arr",
+            Token::ELSE => "else",
+            Token::FOR => "for",
+            Token::FUN => "fun",
+            Token::IF => "if",
+            Token::IMPORT => "import",
+            Token::REC => "rec",
+            Token::THEN => "then",
+            Token::TYP => "typ",
+            Token::VAR => "var",
+            Token::WHERE => "where",
+            Token::WHILE => "while",
+        };
+        write!(f, "`{}`", lexeme)
+    }
+}
@@ -0,0 +1,168 @@
+use lexanal::symbol::Token;
+
+
+/** Razred znakov v vzorcu osnovnega simbola.
+ *
+ * Ne-ASCII znak je lahko v razredu le glede na to, ali je crka (<code>char::is_alphabetic</code>), kontrolni znak
+ * (<code>char::is_control</code>) ali kaj drugega, saj koncni avtomat vse ne-ASCII znake iz iste skupine obravnava enako.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CharClass
+{
+    // crka ali _
+    Letter,
+    // crka, desetiska stevka ali _
+    LetterOrDigit,
+    // desetiska stevka
+    Digit,
+    // sestnajstiska stevka
+    HexDigit,
+    // osmiska stevka (0 do 7)
+    OctalDigit,
+    // dvojiska stevka
+    BinaryDigit,
+    // znak znakovne konstante: vse razen ", \ in kontrolnih znakov; tabulator in konca vrstic sta dovoljena
+    StringChar,
+}
+
+impl CharClass
+{
+    pub fn contains(&self, chr : char) -> bool
+    {
+        match *self
+        {
+            CharClass::Letter => chr.is_alphabetic() || chr == '_',
+            CharClass::LetterOrDigit => chr.is_alphabetic() || chr.is_ascii_digit() || chr == '_',
+            CharClass::Digit => chr.is_ascii_digit(),
+            CharClass::HexDigit => chr.is_ascii_hexdigit(),
+            CharClass::OctalDigit => chr.is_digit(8),
+            CharClass::BinaryDigit => chr.is_digit(2),
+            CharClass::StringChar => chr != '"' && chr != '\\' && (!chr.is_control() || chr == '\t' || chr == '\n' || chr == '\r'),
+        }
+    }
+}
+
+/** Regularni izraz, ki opisuje besedilo sestavljenega osnovnega simbola (npr. konstante).
+ *
+ * Besedila v izrazu so iz znakov ASCII; ne-ASCII znake opisujejo le razredi znakov.
+ */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Regex
+{
+    // natanko podano besedilo
+    Text(&'static str),
+    // en znak iz razreda
+    Class(CharClass),
+    // izrazi drug za drugim
+    Sequence(&'static [Regex]),
+    // eden od izrazov
+    Choice(&'static [Regex]),
+    // izraz ali prazno besedilo
+    Optional(&'static Regex),
+    // poljubno mnogo ponovitev izraza, tudi nobena
+    Repeat(&'static Regex),
+}
+
+/** Vzorec besedila osnovnega simbola. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TokenPattern
+{
+    // natanko podano besedilo (rezervirana beseda, operator ali locilo)
+    Literal(&'static str),
+    // znak iz prvega razreda, ki mu sledi poljubno mnogo znakov iz drugega razreda
+    Repeat(CharClass, CharClass),
+    // besedilo, ki ustreza regularnemu izrazu (konstante)
+    Regex(Regex),
+}
+
+// stevke v podani osnovi, med katerimi (in pred katerimi) je lahko locilo _; vsaj ena stevka je obvezna
+const SEPARATORS : Regex = Regex::Repeat(&Regex::Text("_"));
+const DECIMAL_DIGITS : Regex = Regex::Sequence(&[SEPARATORS, Regex::Class(CharClass::Digit),
+                                                 Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::Digit), Regex::Text("_")]))]);
+const HEX_DIGITS : Regex = Regex::Sequence(&[SEPARATORS, Regex::Class(CharClass::HexDigit),
+                                             Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::HexDigit), Regex::Text("_")]))]);
+const OCTAL_DIGITS : Regex = Regex::Sequence(&[SEPARATORS, Regex::Class(CharClass::OctalDigit),
+                                               Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::OctalDigit), Regex::Text("_")]))]);
+const BINARY_DIGITS : Regex = Regex::Sequence(&[SEPARATORS, Regex::Class(CharClass::BinaryDigit),
+                                                Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::BinaryDigit), Regex::Text("_")]))]);
+// desetisko stevilo se zacne s stevko, ne z locilom
+const DECIMAL : Regex = Regex::Sequence(&[Regex::Class(CharClass::Digit),
+                                          Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::Digit), Regex::Text("_")]))]);
+const EXPONENT : Regex = Regex::Sequence(&[Regex::Choice(&[Regex::Text("e"), Regex::Text("E")]),
+                                           Regex::Optional(&Regex::Choice(&[Regex::Text("+"), Regex::Text("-")])), DECIMAL_DIGITS]);
+
+const HEX : Regex = Regex::Class(CharClass::HexDigit);
+// ubezna zaporedja za znakom \: \\ \" \' \n \t \r \0, \xHH do \x7F in \u{H...} z eno do sestimi stevkami
+const ESCAPE : Regex = Regex::Choice(&[
+    Regex::Text("\\"), Regex::Text("\""), Regex::Text("'"), Regex::Text("n"), Regex::Text("t"), Regex::Text("r"), Regex::Text("0"),
+    Regex::Sequence(&[Regex::Text("x"), Regex::Class(CharClass::OctalDigit), HEX]),
+    Regex::Sequence(&[Regex::Text("u{"), HEX, Regex::Optional(&HEX), Regex::Optional(&HEX), Regex::Optional(&HEX), Regex::Optional(&HEX),
+                      Regex::Optional(&HEX), Regex::Text("}")]),
+]);
+
+/** Osnovni simboli, ki jih prepozna koncni avtomat leksikalnega analizatorja.
+ *
+ * Avtomat prebere najdaljse besedilo, ki ustreza kateremu od vzorcev; ce mu ustreza vec vzorcev, obvelja prvi
+ * v tabeli (zato so rezervirane besede pred imeni). Vrednosti konstant izracuna modul <code>literal</code>.
+ * Komentarji niso v tabeli, saj jih preskoci leksikalni analizator sam.
+ */
+pub const TOKEN_TABLE : &[(Token, TokenPattern)] = &[
+    // rezervirane besede
+    (Token::INT, TokenPattern::Literal("int")),
+    (Token::REAL, TokenPattern::Literal("real")),
+    (Token::BOOL, TokenPattern::Literal("bool")),
+    (Token::STRING, TokenPattern::Literal("string")),
+    (Token::ARR, TokenPattern::Literal("arr")),
+    (Token::ELSE, TokenPattern::Literal("else")),
+    (Token::FOR, TokenPattern::Literal("for")),
+    (Token::FUN, TokenPattern::Literal("fun")),
+    (Token::IF, TokenPattern::Literal("if")),
+    (Token::IMPORT, TokenPattern::Literal("import")),
+    (Token::REC, TokenPattern::Literal("rec")),
+    (Token::THEN, TokenPattern::Literal("then")),
+    (Token::TYP, TokenPattern::Literal("typ")),
+    (Token::VAR, TokenPattern::Literal("var")),
+    (Token::WHERE, TokenPattern::Literal("where")),
+    (Token::WHILE, TokenPattern::Literal("while")),
+    (Token::BOOLCONST, TokenPattern::Literal("true")),
+    (Token::BOOLCONST, TokenPattern::Literal("false")),
+    // operatorji
+    (Token::ADD, TokenPattern::Literal("+")),
+    (Token::SUB, TokenPattern::Literal("-")),
+    (Token::MUL, TokenPattern::Literal("*")),
+    (Token::DIV, TokenPattern::Literal("/")),
+    (Token::MOD, TokenPattern::Literal("%")),
+    (Token::NOT, TokenPattern::Literal("!")),
+    (Token::AND, TokenPattern::Literal("&")),
+    (Token::OR, TokenPattern::Literal("|")),
+    (Token::EQU, TokenPattern::Literal("==")),
+    (Token::NEQ, TokenPattern::Literal("<>")),
+    (Token::LTH, TokenPattern::Literal("<")),
+    (Token::GTH, TokenPattern::Literal(">")),
+    (Token::LEQ, TokenPattern::Literal("<=")),
+    (Token::GEQ, TokenPattern::Literal(">=")),
+    (Token::ASSIGN, TokenPattern::Literal("=")),
+    // locila
+    (Token::LPARENT, TokenPattern::Literal("(")),
+    (Token::RPARENT, TokenPattern::Literal(")")),
+    (Token::LBRACKET, TokenPattern::Literal("[")),
+    (Token::RBRACKET, TokenPattern::Literal("]")),
+    (Token::LBRACE, TokenPattern::Literal("{")),
+    (Token::RBRACE, TokenPattern::Literal("}")),
+    (Token::DOT, TokenPattern::Literal(".")),
+    (Token::COMMA, TokenPattern::Literal(",")),
+    (Token::COLON, TokenPattern::Literal(":")),
+    (Token::SEMIC, TokenPattern::Literal(";")),
+    // imena
+    (Token::IDENTIFIER, TokenPattern::Repeat(CharClass::Letter, CharClass::LetterOrDigit)),
+    // konstante
+    (Token::INTCONST, TokenPattern::Regex(DECIMAL)),
+    (Token::INTCONST, TokenPattern::Regex(Regex::Sequence(&[Regex::Text("0x"), HEX_DIGITS]))),
+    (Token::INTCONST, TokenPattern::Regex(Regex::Sequence(&[Regex::Text("0o"), OCTAL_DIGITS]))),
+    (Token::INTCONST, TokenPattern::Regex(Regex::Sequence(&[Regex::Text("0b"), BINARY_DIGITS]))),
+    (Token::REALCONST, TokenPattern::Regex(Regex::Sequence(&[DECIMAL, Regex::Text("."), DECIMAL_DIGITS, Regex::Optional(&EXPONENT)]))),
+    (Token::STRINGCONST, TokenPattern::Regex(Regex::Sequence(&[Regex::Text("\""),
+                                                               Regex::Repeat(&Regex::Choice(&[Regex::Class(CharClass::StringChar),
+                                                                                              Regex::Sequence(&[Regex::Text("\\"), ESCAPE])])),
+                                                               Regex::Text("\"")]))),
+];
@@ -0,0 +1,66 @@
+use lexanal::position::Position;
+
+
+/** Vrsta dela izvorne kode, ki ne spada k nobenemu osnovnemu simbolu. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TriviaKind
+{
+    // presledki in tabulatorji
+    Whitespace,
+    // LF, CRLF ali CR
+    Newline,
+    // komentar # do konca vrstice (brez konca vrstice)
+    Comment,
+    // komentar #{ ... }#
+    BlockComment,
+    // dokumentacijski komentar ## do konca vrstice (brez konca vrstice)
+    DocComment,
+}
+
+/** Del izvorne kode med osnovnimi simboli (presledki, konci vrstic in komentarji).
+ *
+ * Leksikalni analizator ga zabelezi le v nacinu brez izgub (glej <code>LexicalAnalyzer::set_lossless</code>).
+ */
+#[derive(Debug, Clone)]
+pub struct Trivia
+{
+    kind : TriviaKind,
+    text : String,
+    position : Position,
+}
+
+impl Trivia
+{
+    pub fn new(kind : TriviaKind, text : String, position : Position) -> Trivia
+    {
+        Trivia { kind, text, position }
+    }
+
+    pub fn get_kind(&self) -> TriviaKind
+    {
+        self.kind
+    }
+
+    /** Vrne besedilo, kot je zapisano v izvorni kodi.  */
+    pub fn get_text(&self) -> &str
+    {
+        &self.text
+    }
+
+    pub fn get_position(&self) -> &Position
+    {
+        &self.position
+    }
+
+    pub fn shift(&mut self, line_delta : i64, offset_delta : isize)
+    {
+        self.position.shift(line_delta, offset_delta);
+    }
+
+    // prikljuci neposredno sledeci del iste vrste
+    pub fn extend(&mut self, text : &str, position : &Position)
+    {
+        self.text.push_str(text);
+        self.position.set_max(position);
+    }
+}
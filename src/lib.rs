@@ -0,0 +1,11 @@
+pub mod interner;
+pub mod lexanal;
+pub mod synanal;
+pub mod xml;
+pub mod report;
+pub mod abstree;
+pub mod compiler;
+pub mod driver;
+
+pub use compiler::{compile, compile_str, CompilationResult, CompileOptions, Phase};
+pub use compiler::{parse_expression_str, parse_type_str, parse_declarations_str};
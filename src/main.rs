@@ -1,17 +1,7 @@
-pub mod lexanal;
-pub mod synanal;
-pub mod xml;
-pub mod report;
-pub mod abstree;
+extern crate proteus_compiler;
 
-
-
-//use  lexanal::LexToken;
+use proteus_compiler::driver;
 
 fn main() {
-    let program_name = "p21.proteus".to_string();
-   // println!("Current working directory: {:?}", std::env::current_dir().unwrap());
-
-    //lexanal::run::run(program_name);
-    synanal::run::run(program_name);
+    std::process::exit(driver::main());
 }
@@ -0,0 +1,197 @@
+pub mod snippet;
+
+use lexanal::position::Position;
+use lexanal::symbol::Token;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::fmt::{Error,Formatter};
+use std::rc::Rc;
+
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum ExitCode
+{
+    LexicalAnalyzerIlegallChar,
+    SyntaxAnalyzerSyntaxError,
+    SyntaxAnalyzerUnexpectedEndOfStream,
+    SyntaxAnalyzerImportError,
+    AbstractSyntaxTreeInvalidExpression,
+}
+
+impl ExitCode
+{
+    /** Vrne izhodni status procesa, s katerim se prevajanje konca ob tej napaki.  */
+    pub fn get_status(&self) -> i32
+    {
+        match *self
+        {
+            ExitCode::LexicalAnalyzerIlegallChar => 10,
+            ExitCode::SyntaxAnalyzerSyntaxError => 20,
+            ExitCode::SyntaxAnalyzerUnexpectedEndOfStream => 21,
+            ExitCode::SyntaxAnalyzerImportError => 22,
+            ExitCode::AbstractSyntaxTreeInvalidExpression => 30,
+        }
+    }
+}
+
+impl Display for ExitCode {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error>
+    {
+        match self {
+            &ExitCode::LexicalAnalyzerIlegallChar => writeln!(f, "LexicalAnalyzerIlegallChar"),
+            &ExitCode::SyntaxAnalyzerSyntaxError => writeln!(f, "SyntaxAnalyzerSyntaxError"),
+            &ExitCode::SyntaxAnalyzerUnexpectedEndOfStream => writeln!(f, "SyntaxAnalyzerUnexpectedEndOfStream"),
+            &ExitCode::SyntaxAnalyzerImportError => writeln!(f, "SyntaxAnalyzerImportError"),
+            &ExitCode::AbstractSyntaxTreeInvalidExpression => writeln!(f, "AbstractSyntaxTreeInvalidExpression"),
+        }
+    }
+}
+
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Severity
+{
+    Warning,
+    Error,
+}
+
+
+/** Dodatna oznaka obvestila, ki opozori na drug del vhodne datoteke.  */
+#[derive(Clone,Debug)]
+pub struct Label
+{
+    pub position : Position,
+    pub message : String,
+}
+
+
+/** Obvestilo o napaki ali opozorilo, ki ga sporoci posamezna faza prevajanja.  */
+#[derive(Clone,Debug)]
+pub struct Diagnostic
+{
+    pub severity : Severity,
+    // del vhodne datoteke, na katerega se obvestilo nanasa
+    pub position : Option<Position>,
+    // samo pri napakah
+    pub exit_code : Option<ExitCode>,
+    pub message : String,
+    pub labels : Vec<Label>,
+    // vrste simbolov, ki bi jih sintaksni analizator na mestu napake sprejel (prazno, ce niso znane)
+    pub expected : Vec<Token>,
+}
+
+impl Diagnostic
+{
+    pub fn warning(msg : &str, position : Option<&Position>) -> Diagnostic
+    {
+        Diagnostic { severity : Severity::Warning, position : position.cloned(), exit_code : None, message : msg.to_string(), labels : Vec::new(),
+                     expected : Vec::new() }
+    }
+
+    pub fn error(msg : &str, position : Option<&Position>, exit_code : ExitCode) -> Diagnostic
+    {
+        Diagnostic { severity : Severity::Error, position : position.cloned(), exit_code : Some(exit_code), message : msg.to_string(), labels : Vec::new(),
+                     expected : Vec::new() }
+    }
+
+    /** Obvestilu doda oznako, ki se pri izpisu izvorne kode podcrta s <code>-</code>.  */
+    pub fn with_label(mut self, position : &Position, msg : &str) -> Diagnostic
+    {
+        self.labels.push(Label { position : position.clone(), message : msg.to_string() });
+        self
+    }
+
+    /** Obvestilu doda vrste simbolov, ki bi jih sintaksni analizator na mestu napake sprejel.  */
+    pub fn with_expected(mut self, expected : &[Token]) -> Diagnostic
+    {
+        self.expected.extend_from_slice(expected);
+        self
+    }
+
+    pub fn is_error(&self) -> bool
+    {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error>
+    {
+        let smiley = match self.severity
+        {
+            Severity::Warning => ":-o",
+            Severity::Error => ":-(",
+        };
+        match self.position
+        {
+            Some(ref position) => write!(f, "{} {} {}", smiley, position, self.message),
+            None => write!(f, "{} {}", smiley, self.message),
+        }
+    }
+}
+
+
+/** Zbiralnik obvestil, v katerega pisejo vse faze prevajanja.
+ *
+ * Kopije zbiralnika si delijo isti seznam obvestil, zato lahko vsaka faza hrani svojo kopijo.
+ */
+#[derive(Clone,Debug,Default)]
+pub struct Diagnostics
+{
+    diagnostics : Rc<RefCell<Vec<Diagnostic>>>,
+}
+
+impl Diagnostics
+{
+    pub fn new() -> Diagnostics
+    {
+        Diagnostics { diagnostics : Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    pub fn push(&self, diagnostic : Diagnostic)
+    {
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    /** Zabelezi opozorilo.  */
+    pub fn warning(&self, msg : &str)
+    {
+        self.push(Diagnostic::warning(msg, None));
+    }
+
+    /** Zabelezi opozorilo, ki je vezano na del vhodne datoteke.  */
+    pub fn warning_at_position(&self, msg : &str, position : &Position)
+    {
+        self.push(Diagnostic::warning(msg, Some(position)));
+    }
+
+    /** Zabelezi obvestilo o napaki.  */
+    pub fn error(&self, msg : &str, exit_code : ExitCode)
+    {
+        self.push(Diagnostic::error(msg, None, exit_code));
+    }
+
+    /** Zabelezi obvestilo o napaki, ki je vezano na del vhodne datoteke.  */
+    pub fn error_at_position(&self, msg : &str, position : &Position, exit_code : ExitCode)
+    {
+        self.push(Diagnostic::error(msg, Some(position), exit_code));
+    }
+
+    pub fn has_errors(&self) -> bool
+    {
+        self.diagnostics.borrow().iter().any(|diagnostic| diagnostic.is_error())
+    }
+
+    /** Vrne izhodno kodo prve zabelezene napake.  */
+    pub fn get_exit_code(&self) -> Option<ExitCode>
+    {
+        self.diagnostics.borrow().iter().filter_map(|diagnostic| diagnostic.exit_code).next()
+    }
+
+    /** Vrne kopijo vseh do sedaj zabelezenih obvestil.  */
+    pub fn get_diagnostics(&self) -> Vec<Diagnostic>
+    {
+        self.diagnostics.borrow().clone()
+    }
+}
@@ -0,0 +1,207 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use interner::Name;
+use lexanal::position::Position;
+use lexanal::DEFAULT_TAB_WIDTH;
+use report::Diagnostic;
+
+
+/** Hrani vrstice izvornih datotek, ki jih potrebuje izpis obvestil.
+ *
+ * Datoteke se preberejo sele ob prvem izpisu obvestila, ki se nanje nanasa. Tabulatorji v vrsticah so
+ * zamenjani s presledki, tako da se stolpci ujemajo s stolpci leksikalnega analizatorja z enako sirino tabulatorja.
+ */
+pub struct SourceCache
+{
+    sources : HashMap<Name, Option<Vec<String>>>,
+    tab_width : u64,
+}
+
+impl Default for SourceCache
+{
+    fn default() -> SourceCache
+    {
+        SourceCache::new()
+    }
+}
+
+impl SourceCache
+{
+    pub fn new() -> SourceCache
+    {
+        Self::new_with_tab_width(DEFAULT_TAB_WIDTH)
+    }
+
+    /** Ustvari prazen predpomnilnik, ki tabulatorje razsiri do podane sirine.
+    * @param tab_width Sirina tabulatorja, s katero je leksikalni analizator izracunal stolpce.
+    */
+    pub fn new_with_tab_width(tab_width : u64) -> SourceCache
+    {
+        SourceCache { sources : HashMap::new(), tab_width : cmp::max(tab_width, 1) }
+    }
+
+    /** Doda izvorno kodo, ki je ni mogoce (ali ni treba) prebrati z diska.
+    * @param filename Ime datoteke, kot je zapisano v <code>Position</code>.
+    * @param source Izvorna koda.
+    */
+    pub fn add_source(&mut self, filename : &str, source : &str)
+    {
+        let lines = self.split_lines(source);
+        self.sources.insert(Name::new(filename), Some(lines));
+    }
+
+    // konci vrstic so LF, CRLF ali samostojen CR, kot v leksikalnem analizatorju
+    fn split_lines(&self, source : &str) -> Vec<String>
+    {
+        source.split('\n').flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+              .map(|line| self.expand_tabs(line)).collect()
+    }
+
+    fn expand_tabs(&self, line : &str) -> String
+    {
+        let mut expanded = String::new();
+        for chr in line.chars()
+        {
+            if chr == '\t'
+            {
+                let width = self.tab_width as usize;
+                let spaces = width - expanded.chars().count() % width;
+                expanded.push_str(&" ".repeat(spaces));
+            }
+            else
+            {
+                expanded.push(chr);
+            }
+        }
+        expanded
+    }
+
+    fn get_lines(&mut self, filename : Name) -> Option<&Vec<String>>
+    {
+        if !self.sources.contains_key(&filename)
+        {
+            // napacna zaporedja UTF-8 nadomestimo, da se stolpci ujemajo s stolpci leksikalnega analizatorja
+            let mut source = Vec::new();
+            let lines = match File::open(&*filename.as_str()).and_then(|mut file| file.read_to_end(&mut source))
+            {
+                Ok(_) => Some(self.split_lines(&String::from_utf8_lossy(&source))),
+                Err(_) => None,
+            };
+            self.sources.insert(filename, lines);
+        }
+        self.sources.get(&filename).and_then(|lines| lines.as_ref())
+    }
+}
+
+
+// podcrtan del ene vrstice
+struct Mark
+{
+    line : u64,
+    begin_column : u64,
+    // None pomeni do konca vrstice
+    end_column : Option<u64>,
+    underline : char,
+    message : String,
+}
+
+fn add_marks(marks : &mut Vec<Mark>, position : &Position, underline : char, message : &str)
+{
+    if position.get_begin_line() == position.get_end_line()
+    {
+        marks.push(Mark { line : position.get_begin_line(), begin_column : position.get_begin_column(),
+                          end_column : Some(position.get_end_column()), underline, message : message.to_string() });
+    }
+    else
+    {
+        marks.push(Mark { line : position.get_begin_line(), begin_column : position.get_begin_column(),
+                          end_column : None, underline, message : String::new() });
+        marks.push(Mark { line : position.get_end_line(), begin_column : 1,
+                          end_column : Some(position.get_end_column()), underline, message : message.to_string() });
+    }
+}
+
+// vrstica s podcrtajem; tabulatorji v vrstici so ze razsirjeni, zato stolpci ustrezajo znakom
+fn render_mark(line : &str, mark : &Mark) -> String
+{
+    let mut underline = " ".repeat(mark.begin_column.saturating_sub(1) as usize);
+    let end_column = match mark.end_column
+    {
+        Some(end_column) => end_column,
+        None => line.chars().count() as u64,
+    };
+    let width = if end_column >= mark.begin_column { end_column - mark.begin_column + 1 } else { 1 };
+    for _ in 0..width
+    {
+        underline.push(mark.underline);
+    }
+    if !mark.message.is_empty()
+    {
+        underline.push(' ');
+        underline.push_str(&mark.message);
+    }
+    underline
+}
+
+/** Pripravi izpis obvestila skupaj z vrsticami izvorne kode, na katere se obvestilo nanasa.
+ *
+ * Del, na katerega se nanasa obvestilo, je podcrtan z <code>^</code>, dodatne oznake pa z <code>-</code>.
+ * Oznake, ki se nanasajo na druge datoteke, so izpisane kot opombe.
+ */
+pub fn render(diagnostic : &Diagnostic, sources : &mut SourceCache) -> String
+{
+    let mut output = format!("{}", diagnostic);
+    // obvestilo brez polozaja (npr. nepricakovan konec datoteke) izpise le svoje oznake
+    let position = match diagnostic.position.as_ref().or_else(|| diagnostic.labels.first().map(|label| &label.position))
+    {
+        Some(position) => position,
+        None => return output,
+    };
+
+    let mut marks : Vec<Mark> = Vec::new();
+    let mut notes : Vec<String> = Vec::new();
+    if diagnostic.position.is_some()
+    {
+        add_marks(&mut marks, position, '^', "");
+    }
+    for label in &diagnostic.labels
+    {
+        if label.position.get_filename_id() == position.get_filename_id()
+        {
+            add_marks(&mut marks, &label.position, '-', &label.message);
+        }
+        else
+        {
+            notes.push(format!("{} {}", label.position, label.message));
+        }
+    }
+    marks.sort_by_key(|mark| (mark.line, mark.begin_column));
+
+    if let Some(lines) = sources.get_lines(position.get_filename_id())
+    {
+        let gutter = " ".repeat(marks.iter().map(|mark| mark.line).max().unwrap_or(1).to_string().len());
+        output.push_str(&format!("\n{} |", gutter));
+        let mut previous_line : Option<u64> = None;
+        for mark in &marks
+        {
+            let line = lines.get((mark.line as usize).saturating_sub(1)).map(|line| line.as_str()).unwrap_or("");
+            if previous_line != Some(mark.line)
+            {
+                if previous_line.is_some_and(|previous_line| mark.line > previous_line + 1)
+                {
+                    output.push_str("\n...");
+                }
+                output.push_str(&format!("\n{:>width$} | {}", mark.line, line, width = gutter.len()));
+                previous_line = Some(mark.line);
+            }
+            output.push_str(&format!("\n{} | {}", gutter, render_mark(line, mark)));
+        }
+    }
+    for note in notes
+    {
+        output.push_str(&format!("\n  = note: {}", note));
+    }
+    output
+}
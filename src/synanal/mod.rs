@@ -1,14 +1,20 @@
 pub mod parse_error;
+pub mod trace;
 pub mod run;
 
 
-use std::io::Write;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
 use std::io;
-use xml::{ProteusXmlCreator, XMLable};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use xml::ProteusXmlCreator;
 use lexanal::LexicalAnalyzer;
-use lexanal::symbol::{Symbol, Token};
+use lexanal::symbol::{LiteralValue, Symbol, Token};
 use lexanal::position::Position;
 use synanal::parse_error::{ParseError,SymbolError};
+use synanal::trace::{ParseTracer, XmlTracer};
 use abstree::AbsTree;
 use abstree::abs_decl::*;
 use abstree::abs_expr::*;
@@ -16,14 +22,132 @@ use abstree::abs_position::AbsPosition;
 use abstree::abs_stmt::*;
 use abstree::abs_type::*;
 use abstree::positioner::Positioner;
-use report;
+use report::{Diagnostic, Diagnostics, ExitCode};
+
+
+// simboli, na katerih se sintaksni analizator po napaki v seznamu izrazov sinhronizira
+const EXPRESSIONS_SYNC : [Token; 9] = [Token::COMMA, Token::RPARENT, Token::RBRACE, Token::SEMIC, Token::ELSE, Token::TYP, Token::FUN, Token::VAR, Token::IMPORT];
+// simboli, na katerih se sintaksni analizator po napaki v deklaraciji sinhronizira
+const DECLARATIONS_SYNC : [Token; 8] = [Token::SEMIC, Token::TYP, Token::FUN, Token::VAR, Token::IMPORT, Token::RPARENT, Token::RBRACE, Token::COMMA];
+// simboli, na katerih se sintaksni analizator po napaki v seznamu parametrov ali komponent sinhronizira
+const COMPOMENTS_SYNC : [Token; 2] = [Token::COMMA, Token::RPARENT];
+
+// simboli, s katerimi se lahko zacne izraz (za sporocila o napakah)
+const EXPRESSION_FIRST : [Token; 12] = [Token::ADD, Token::SUB, Token::MUL, Token::AND, Token::NOT, Token::INTCONST, Token::REALCONST,
+                                        Token::BOOLCONST, Token::STRINGCONST, Token::IDENTIFIER, Token::LPARENT, Token::LBRACE];
+// simboli, ki lahko v izrazu sledijo zavitemu oklepaju
+const BRACE_EXPRESSION_FIRST : [Token; 7] = [Token::RBRACE, Token::IDENTIFIER, Token::MUL, Token::LPARENT, Token::IF, Token::FOR, Token::WHILE];
+// simboli, s katerimi se lahko zacne leva stran prirejanja
+const LVALUE_FIRST : [Token; 3] = [Token::IDENTIFIER, Token::MUL, Token::LPARENT];
+// simboli, s katerimi se lahko zacne deklaracija
+const DECLARATION_FIRST : [Token; 4] = [Token::TYP, Token::FUN, Token::VAR, Token::IMPORT];
+// simboli, s katerimi se lahko zacne opis tipa
+const TYPE_FIRST : [Token; 10] = [Token::INT, Token::REAL, Token::BOOL, Token::STRING, Token::LBRACE, Token::IDENTIFIER,
+                                  Token::MUL, Token::ARR, Token::REC, Token::LPARENT];
+
+
+/** Privzeta najvecja globina gnezdenja izrazov in tipov (glej <code>SyntaxAnalyzer::set_max_nesting_depth</code>).
+ *
+ * Na tej globini analiza (tudi v razhroscevalni izdelavi) porabi manj kot polovico obicajnega 8 MB sklada.
+ */
+pub const DEFAULT_MAX_NESTING_DEPTH : usize = 100;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Associativity
+{
+    // a - b - c je (a - b) - c
+    Left,
+    // a < b < c ni izraz
+    None,
+}
+
+// binarni operatorji s prioriteto (vecja veze mocneje) in asociativnostjo
+const BINARY_OPERATORS : [(Token, u32, Associativity); 13] =
+[
+    (Token::OR, 1, Associativity::Left),
+    (Token::AND, 2, Associativity::Left),
+    (Token::EQU, 3, Associativity::None), (Token::NEQ, 3, Associativity::None), (Token::LEQ, 3, Associativity::None),
+    (Token::GEQ, 3, Associativity::None), (Token::LTH, 3, Associativity::None), (Token::GTH, 3, Associativity::None),
+    (Token::ADD, 4, Associativity::Left), (Token::SUB, 4, Associativity::Left),
+    (Token::MUL, 5, Associativity::Left), (Token::DIV, 5, Associativity::Left), (Token::MOD, 5, Associativity::Left),
+];
+// predponski operatorji; vezejo mocneje od vseh binarnih
+const PREFIX_OPERATORS : [Token; 5] = [Token::ADD, Token::SUB, Token::MUL, Token::AND, Token::NOT];
+
+fn get_binary_operator(token : Token) -> Option<(Token, u32, Associativity)>
+{
+    BINARY_OPERATORS.iter().find(|&&(operator, _, _)| operator == token).cloned()
+}
+
+fn get_abs_bin_oper(token : Token) -> AbsBinOper
+{
+    match token
+    {
+        Token::OR => AbsBinOper::OR,
+        Token::AND => AbsBinOper::AND,
+        Token::EQU => AbsBinOper::EQU,
+        Token::NEQ => AbsBinOper::NEQ,
+        Token::LEQ => AbsBinOper::LEQ,
+        Token::GEQ => AbsBinOper::GEQ,
+        Token::LTH => AbsBinOper::LTH,
+        Token::GTH => AbsBinOper::GTH,
+        Token::ADD => AbsBinOper::ADD,
+        Token::SUB => AbsBinOper::SUB,
+        Token::MUL => AbsBinOper::MUL,
+        Token::DIV => AbsBinOper::DIV,
+        Token::MOD => AbsBinOper::MOD,
+        _ => unreachable!("{:?} is not a binary operator", token),
+    }
+}
+
+fn get_abs_un_oper(token : Token) -> AbsUnOper
+{
+    match token
+    {
+        Token::ADD => AbsUnOper::ADD,
+        Token::SUB => AbsUnOper::SUB,
+        Token::MUL => AbsUnOper::MUL,
+        Token::AND => AbsUnOper::AND,
+        Token::NOT => AbsUnOper::NOT,
+        _ => unreachable!("{:?} is not a prefix operator", token),
+    }
+}
+
+// zdruzi zadnja operanda z zadnjim operatorjem na skladu
+fn reduce_binary_expression(operands : &mut Vec<Box<AbsExpr>>, operators : &mut Vec<(Token, u32)>)
+{
+    let (token, _) = operators.pop().unwrap();
+    let right = operands.pop().unwrap();
+    let left = operands.pop().unwrap();
+    operands.push(Box::new(AbsBinExpr::new(get_abs_bin_oper(token), left, right)));
+}
 
 
 pub struct SyntaxAnalyzer //<'a> 
 {
     lexical_analyser : LexicalAnalyzer,
-    xml_creator : Option<ProteusXmlCreator>,
+    tracer : Option<Box<ParseTracer>>,
+    // prva napaka pri zapisovanju drevesa izpeljave; po njej se zapisovanje ustavi
+    trace_error : Option<io::Error>,
     symbol : Option<Symbol>,
+    diagnostics : Diagnostics,
+    // globina gnezdenja produkcij
+    depth : usize,
+    // stevilo prebranih simbolov
+    consumed : usize,
+    // stevilo prebranih simbolov ob zadnji sinhronizaciji
+    recovered_at : Option<usize>,
+    // simboli, ki bi jih na trenutnem mestu sprejeli ze zakljuceni seznami
+    expected : Vec<Token>,
+    // globina gnezdenja izrazov in tipov
+    nesting : usize,
+    max_nesting_depth : usize,
+    // direktoriji, v katerih se iscejo uvozene datoteke, ki jih ni v direktoriju uvazajoce datoteke
+    import_path : Vec<PathBuf>,
+    // datoteke, ki (posredno) uvazajo trenutno datoteko: kanonicna pot in ime za sporocila
+    importers : Vec<(PathBuf, String)>,
+    // kanonicne poti vseh datotek, ki so bile ze uvozene (skupne vsem analizatorjem uvozenih datotek)
+    imported : Rc<RefCell<HashSet<PathBuf>>>,
 }
 
 //impl<'a> SyntaxAnalyzer<'a> 
@@ -34,330 +158,341 @@ impl SyntaxAnalyzer
     {
         SyntaxAnalyzer 
         {
+            diagnostics : lexical_analyser.get_diagnostics(),
             lexical_analyser : lexical_analyser,
-            xml_creator : None,
-            symbol : None
+            tracer : None,
+            trace_error : None,
+            symbol : None,
+            depth : 0,
+            consumed : 0,
+            recovered_at : None,
+            expected : Vec::new(),
+            nesting : 0,
+            max_nesting_depth : DEFAULT_MAX_NESTING_DEPTH,
+            import_path : Vec::new(),
+            importers : Vec::new(),
+            imported : Rc::new(RefCell::new(HashSet::new())),
         }
     }
 
+    /** Ustvari sintaksni analizator, ki drevo izpeljave zapise v XML obliki (glej <code>XmlTracer</code>).  */
     pub fn new_with_xml_creator(lexical_analyser : LexicalAnalyzer, xml_creator : ProteusXmlCreator) -> SyntaxAnalyzer //<'a>
     {
-        SyntaxAnalyzer 
-        {
-            lexical_analyser : lexical_analyser,
-            xml_creator : Some(xml_creator),
-            symbol : None
-        }
+        Self::new_with_tracer(lexical_analyser, Box::new(XmlTracer::new(xml_creator)))
     }
 
+    /** Ustvari sintaksni analizator, ki med analizo o produkcijah in prebranih simbolih obvesca podani <code>tracer</code>.  */
+    pub fn new_with_tracer(lexical_analyser : LexicalAnalyzer, tracer : Box<ParseTracer>) -> SyntaxAnalyzer
+    {
+        let mut syntax_analyzer = SyntaxAnalyzer::new(lexical_analyser);
+        syntax_analyzer.tracer = Some(tracer);
+        syntax_analyzer
+    }
+
+    /** Nastavi najvecjo globino gnezdenja izrazov in tipov (npr. oklepajev v <code>((a))</code>).
+    *
+    * Globlje gnezdenje se javi kot sintaksna napaka, namesto da bi rekurzivna analiza prekoracila sklad.
+    * Izrazi in tipi si globino delijo, saj lahko tip vsebuje izraz (<code>arr[e]</code>) in izraz tip
+    * (deklaracije za <code>where</code>): <code>arr[(a)] int</code> je gnezden tri ravni globoko.
+    * Vsaka raven porabi do priblizno 40 KB sklada (v razhroscevalni izdelavi), zato vecja globina potrebuje
+    * nit z ustrezno vecjim skladom.
+    */
+    pub fn set_max_nesting_depth(&mut self, max_nesting_depth : usize)
+    {
+        self.max_nesting_depth = max_nesting_depth;
+    }
 
+    /** Nastavi direktorije, v katerih se iscejo datoteke deklaracij <code>import</code>.
+    *
+    * Relativna pot v deklaraciji se najprej isce glede na direktorij uvazajoce datoteke, nato pa po vrsti v teh direktorijih.
+    */
+    pub fn set_import_path(&mut self, import_path : Vec<PathBuf>)
+    {
+        self.import_path = import_path;
+    }
+
+    /** Konca sintaksno analizo in vrne leksikalni analizator, iz katerega je brala simbole.  */
+    pub fn into_lexical_analyser(self) -> LexicalAnalyzer
+    {
+        self.lexical_analyser
+    }
+
+    /** Vrne zbiralnik obvestil, v katerega pisejo leksikalni in sintaksni analizator.  */
+    pub fn get_diagnostics(&self) -> Diagnostics
+    {
+        self.diagnostics.clone()
+    }
+
+
+    /** Izvede sintaksno analizo programa.
+    *
+    * Sintaksne napake so zabelezene v zbiralniku obvestil, simboli <code>ERROR</code> pa so preskoceni. Po vsaki
+    * napaki se sintaksni analizator sinhronizira in nadaljuje, zato je vrnjeno drevo lahko nepopolno. Vrednost
+    * <code>None</code> je vrnjena, ce program ne vsebuje nobenega pravilnega izraza.
+    */
     pub fn parse(&mut self) -> Result<Option<Box<AbsTree>>, io::Error> 
     {
-        self.symbol = match self.lexical_analyser.get_next_symbol()
+        self.symbol = match self.next_symbol()
         {
             Ok(Some(symbol)) => Some(symbol),
             Ok(None) => return Ok(None),
-            Err(ioe) => return Err(ioe),
+            Err(ParseError::IoError(ioe)) => return Err(ioe),
+            Err(_) => return Ok(None),
         }; 
         
         let result = self.parse_source();
+        if let Some(ioe) = self.trace_error.take()
+        {
+            return Err(ioe);
+        }
         match result 
         {
-            Ok(expr) => 
-            {
-                //at the end of parsing, self.symbol must be None
-                 if self.symbol.is_some()
-                {
-                    report::error("Internal error: Syntax check finished, but symbol still available",report::ExitCode::SyntaxAnalyzerSyntaxError);
-                }
-
-                return Ok(Some(expr));
-            },
-            Err(ParseError::IoError(ioe)) => return Err(ioe),
+            Ok(expr) => Ok(Some(expr)),
+            Err(ParseError::IoError(ioe)) => Err(ioe),
+            Err(ParseError::Recovered) => Ok(None),
             Err(ParseError::SyntaxError(syerr)) => 
             {
-                match syerr.get_ref_symbol() 
-                {
-                    Some(symbol) => report::error_at_position(&format!("{}",syerr),symbol.get_ref_position(),report::ExitCode::SyntaxAnalyzerSyntaxError),
-                    None => report::error(&format!("{}",syerr),report::ExitCode::SyntaxAnalyzerUnexpectedEndOfStream),
-                }
+                self.report_syntax_error(&syerr);
                 Ok(None)
             }
         }
     }
 
-
-    fn parse_source(&mut self) -> Result<Box<AbsTree>, ParseError> 
+    /** Razcleni en izraz (npr. <code>a[i].x + 1</code>), ki mora obsegati ves vhod.
+    *
+    * Napake so zabelezene v zbiralniku obvestil; ce izraza ni mogoce razcleniti ali mu sledijo se drugi
+    * simboli, je vrnjena vrednost <code>None</code>.
+    */
+    pub fn parse_expression_fragment(&mut self) -> Result<Option<Box<AbsExpr>>, io::Error>
     {
-        self.debug("parse_source");
-        let abstree = try!(self.parse_expressions());
-        self.debug_end();
-        Ok(abstree)
+        self.parse_fragment(|syntax_analyzer| syntax_analyzer.parse_expression())
     }
 
-    fn parse_expressions(&mut self) -> Result<Box<AbsExprs>,ParseError> 
+    /** Razcleni opis tipa (npr. <code>arr[10] *rec(x:int)</code>), ki mora obsegati ves vhod (glej <code>parse_expression_fragment</code>).  */
+    pub fn parse_type_fragment(&mut self) -> Result<Option<Box<AbsType>>, io::Error>
     {
-        self.debug("parse_expressions");
-        let mut abs_exprs = Box::new(AbsExprs::new());
-        let abs_expr = self.parse_expression()?;
-        abs_exprs.exprs.push(abs_expr);
-        abs_exprs = self.parse_expressions_rest(abs_exprs)?;
-        abs_exprs.calculate_abs_position();
-        self.debug_end();
-        Ok(abs_exprs)
+        self.parse_fragment(|syntax_analyzer| syntax_analyzer.parse_type())
     }
 
-
-    fn parse_expression(&mut self) -> Result<Box<AbsExpr>,ParseError> 
+    /** Razcleni seznam deklaracij, ki mora obsegati ves vhod (glej <code>parse_expression_fragment</code>).
+    *
+    * Tako kot v programu se analizator po napaki v deklaraciji sinhronizira, zato je vrnjeni seznam lahko nepopoln.
+    */
+    pub fn parse_declarations_fragment(&mut self) -> Result<Option<AbsDecls>, io::Error>
     {
-        self.debug("parse_expression");
-        let abs_expr =self.parse_or_expression()?;
-        self.debug_end();
-        Ok(abs_expr)
+        self.parse_fragment(|syntax_analyzer| syntax_analyzer.parse_declarations())
     }
 
-     fn parse_expressions_rest(&mut self, abs_exprs_arg : Box<AbsExprs>) -> Result<Box<AbsExprs>,ParseError> 
+    // prebere prvi simbol, izvede produkcijo in preveri, da je prebran ves vhod
+    fn parse_fragment<T, F>(&mut self, production : F) -> Result<Option<T>, io::Error> where F : FnOnce(&mut SyntaxAnalyzer) -> Result<T, ParseError>
     {
-        self.debug("parse_expressions_rest");
-        let mut abs_exprs = abs_exprs_arg;
-        match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+        self.symbol = match self.next_symbol()
+        {
+            Ok(symbol) => symbol,
+            Err(ParseError::IoError(ioe)) => return Err(ioe),
+            Err(_) => None,
+        };
+
+        let result = match production(self)
+        {
+            Ok(_) if self.symbol.is_some() => Err(ParseError::SyntaxError(self.unexpected(&[Token::EOF]))),
+            result => result,
+        };
+        // produkcije, ki jih je napaka prekinila, se zakljucijo
+        while self.depth > 0
         {
-            Some(Token::COMMA)  => 
+            self.debug_end();
+        }
+        if let Some(ioe) = self.trace_error.take()
+        {
+            return Err(ioe);
+        }
+        match result
+        {
+            Ok(fragment) => Ok(Some(fragment)),
+            Err(ParseError::IoError(ioe)) => Err(ioe),
+            Err(ParseError::Recovered) => Ok(None),
+            Err(ParseError::SyntaxError(syerr)) =>
             {
-                self.skip(Token::COMMA)?;
-                abs_exprs.exprs.push(self.parse_expression()?);
-                abs_exprs = self.parse_expressions_rest(abs_exprs)?;
-            },
-            _ => {},
+                self.report_syntax_error(&syerr);
+                Ok(None)
+            }
         }
-        self.debug_end();
-        Ok(abs_exprs)
     }
 
-    fn parse_or_expression(&mut self) -> Result<Box<AbsExpr>, ParseError>
+    fn report_syntax_error(&self, syerr : &SymbolError)
     {
-        self.debug("parse_or_expression");
-        let abs_expr = self.parse_and_expression()?;
-        let expr = self.parse_or_expression_rest(abs_expr)?;
-        self.debug_end();
-        Ok(expr)
+        let mut diagnostic = match syerr.get_ref_symbol() 
+        {
+            Some(symbol) => Diagnostic::error(&format!("{}",syerr),Some(symbol.get_ref_position()),ExitCode::SyntaxAnalyzerSyntaxError),
+            None => Diagnostic::error(&format!("{}",syerr),None,ExitCode::SyntaxAnalyzerUnexpectedEndOfStream),
+        };
+        diagnostic.labels.extend(syerr.get_labels().iter().cloned());
+        self.diagnostics.push(diagnostic.with_expected(syerr.get_expected()));
     }
 
-    fn parse_or_expression_rest(&mut self, abs_expr_arg : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+
+    fn parse_source(&mut self) -> Result<Box<AbsTree>, ParseError> 
     {
-        let mut abs_expr = abs_expr_arg;
-        self.debug("parse_or_expression_rest");
-        match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+        self.debug("parse_source");
+        let depth = self.depth;
+        let mut abstree : Option<Box<AbsExprs>> = None;
+        loop
         {
-            Some(Token::OR) =>
+            match self.parse_expressions()
             {
-                self.skip(Token::OR)?;
-                let abs_right_expr = self.parse_and_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::OR,abs_expr,abs_right_expr));
-                abs_expr = self.parse_or_expression_rest(abs_bin_expr)?;
-            },
-            _ => {},
+                Ok(abs_exprs) => match abstree
+                {
+                    Some(ref mut abstree) => abstree.exprs.extend(abs_exprs.exprs),
+                    None => abstree = Some(abs_exprs),
+                },
+                Err(ParseError::Recovered) =>
+                {
+                    while self.depth > depth
+                    {
+                        self.debug_end();
+                    }
+                },
+                Err(error) => return Err(error),
+            }
+
+            // odvecen simbol za koncem izrazov (npr. zaklepaj brez oklepaja) preskocimo in nadaljujemo
+            if self.symbol.is_none()
+            {
+                break;
+            }
+            if self.recovered_at != Some(self.consumed)
+            {
+                let syerr = self.unexpected(&[Token::EOF]);
+                self.report_syntax_error(&syerr);
+            }
+            self.symbol = self.next_symbol()?;
+            self.recovered_at = Some(self.consumed);
         }
         self.debug_end();
-        Ok(abs_expr)
+        let mut abstree = match abstree
+        {
+            Some(abstree) => abstree,
+            None => return Err(ParseError::Recovered),
+        };
+        abstree.calculate_abs_position();
+        Ok(abstree)
     }
 
-    fn parse_and_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
+    fn parse_expressions(&mut self) -> Result<Box<AbsExprs>,ParseError> 
     {
-         self.debug("parse_and_expression");
-         let rel_expr = self.parse_relational_expression()?;
-         let expr = self.parse_and_expression_rest(rel_expr)?;
-         self.debug_end();
-         Ok(expr)
+        self.debug("parse_expressions");
+        let mut abs_exprs = Box::new(AbsExprs::new());
+        self.parse_expressions_element(&mut abs_exprs)?;
+        abs_exprs = self.parse_expressions_rest(abs_exprs)?;
+        if abs_exprs.exprs.is_empty()
+        {
+            return Err(ParseError::Recovered);
+        }
+        abs_exprs.calculate_abs_position();
+        self.debug_end();
+        Ok(abs_exprs)
     }
 
-     fn parse_and_expression_rest(&mut self, abs_expr_arg : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+    /** Razcleni izraz v seznamu izrazov; ob sintaksni napaki izraz izpusti in se sinhronizira.  */
+    fn parse_expressions_element(&mut self, abs_exprs : &mut AbsExprs) -> Result<(),ParseError>
     {
-         self.debug("parse_and_expression_rest");
-         let mut abs_expr = abs_expr_arg;
-         match self.symbol.as_ref().map(|symbol|  symbol.get_token())
-         {
-             Some(Token::AND)  => 
-             {
-                 self.skip(Token::AND)?;
-                 let rel_expr = self.parse_relational_expression()?;
-                 let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::AND,abs_expr,rel_expr));
-                 abs_expr = self.parse_and_expression_rest(abs_bin_expr)?;
-             },
-             _ => {},
-         }
-         self.debug_end();
-         Ok(abs_expr)
+        let depth = self.depth;
+        match self.parse_expression()
+        {
+            Ok(abs_expr) => abs_exprs.exprs.push(abs_expr),
+            Err(error) => self.recover(error, depth, &EXPRESSIONS_SYNC)?,
+        }
+        Ok(())
     }
 
-    fn parse_relational_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
-    {
-         self.debug("parse_relational_expression");
-         let mut expr = self.parse_additive_expression()?;        
-         match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
-         {
-             Some(Token::EQU)  => 
-             {
-                 self.skip(Token::EQU)?;
-                 let right_expr = self.parse_additive_expression()?;
-                 expr = Box::new(AbsBinExpr::new(AbsBinOper::EQU, expr, right_expr));
-             },
-             Some(Token::NEQ)  => 
-             {
-                self.skip(Token::NEQ)?;
-                let right_expr = self.parse_additive_expression()?;
-                expr = Box::new(AbsBinExpr::new(AbsBinOper::NEQ, expr, right_expr));
-             },
-             Some(Token::LEQ) =>
-             {
-                self.skip(Token::LEQ)?;
-                let right_expr = self.parse_additive_expression()?;
-                expr = Box::new(AbsBinExpr::new(AbsBinOper::LEQ, expr, right_expr));
-             },
-             Some(Token::GEQ) =>
-             {
-                self.skip(Token::GEQ)?;
-                let right_expr = self.parse_additive_expression()?;
-                expr = Box::new(AbsBinExpr::new(AbsBinOper::GEQ, expr, right_expr));
-             },
-             Some(Token::LTH) =>
-             {
-                self.skip(Token::LTH)?;
-                let right_expr = self.parse_additive_expression()?;
-                expr = Box::new(AbsBinExpr::new(AbsBinOper::LTH, expr, right_expr));
-             },
-             Some(Token::GTH) =>
-             {
-                self.skip(Token::GTH)?;
-                let right_expr = self.parse_additive_expression()?;
-                expr = Box::new(AbsBinExpr::new(AbsBinOper::GTH, expr, right_expr));
-             },
-             _ => {},
-         }
-         self.debug_end();
-         Ok(expr)
-    }
 
-    fn parse_additive_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
+    fn parse_expression(&mut self) -> Result<Box<AbsExpr>,ParseError> 
     {
-        self.debug("parse_additive_expression");
-        let mult_expr = self.parse_multiplicative_expression()?;
-        let expr = self.parse_additive_expression_rest(mult_expr)?;
+        self.enter_nesting("Expression")?;
+        self.debug("parse_expression");
+        let abs_expr = self.parse_binary_expression();
+        self.nesting -= 1;
+        let abs_expr = abs_expr?;
         self.debug_end();
-        Ok(expr)
+        Ok(abs_expr)
     }
 
-    fn parse_additive_expression_rest(&mut self, abs_expr_arg : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+     fn parse_expressions_rest(&mut self, abs_exprs_arg : Box<AbsExprs>) -> Result<Box<AbsExprs>,ParseError> 
     {
-        self.debug("parse_additive_expression_rest");
-        let mut expr = abs_expr_arg;
-        match self.symbol.as_ref().map(|symbol| symbol.get_token())
+        let mut abs_exprs = abs_exprs_arg;
+        let mut rests = 0;
+        loop
         {
-            Some(Token::ADD) => 
+            self.debug("parse_expressions_rest");
+            rests += 1;
+            match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
             {
-                self.skip(Token::ADD)?;
-                let mult_expr = self.parse_multiplicative_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::ADD,expr,mult_expr));
-                expr = self.parse_additive_expression_rest(abs_bin_expr)?
-            },
-            Some(Token::SUB) =>
-            {
-                self.skip(Token::SUB)?;
-                let mult_expr = self.parse_multiplicative_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::SUB,expr,mult_expr));
-                expr = self.parse_additive_expression_rest(abs_bin_expr)?;
+                Some(Token::COMMA)  => 
+                {
+                    self.skip(Token::COMMA)?;
+                    self.parse_expressions_element(&mut abs_exprs)?;
+                },
+                _ =>
+                {
+                    self.expected.push(Token::COMMA);
+                    break;
+                },
             }
-            _ => {},
         }
-        self.debug_end();
-        Ok(expr)
-    }
-
-
-    fn parse_multiplicative_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
-    {
-        self.debug("parse_multiplicative_expression");
-        let pref_expr = self.parse_prefix_expression()?;
-        let expr = self.parse_multiplicative_expression_rest(pref_expr)?;
-        self.debug_end();
-        Ok(expr)
+        self.debug_end_rests(rests);
+        Ok(abs_exprs)
     }
 
-    fn parse_multiplicative_expression_rest(&mut self, abs_expr_arg : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+    /** Razcleni predponske izraze, povezane z binarnimi operatorji, s plezanjem po prioritetah (glej <code>BINARY_OPERATORS</code>).
+    *
+    * Izraz se gradi v zanki s skladom operandov in operatorjev, zato dolga zaporedja operatorjev ne poglabljajo
+    * rekurzije. Levo asociativni operatorji se zdruzujejo z leve; neasociativen operator, ki bi se na isti ravni
+    * ponovil (drugi <code>&lt;</code> v <code>a &lt; b &lt; c</code>), izraz konca.
+    */
+    fn parse_binary_expression(&mut self) -> Result<Box<AbsExpr>, ParseError>
     {
-        self.debug("parse_multiplicative_expression_rest");
-        let mut expr = abs_expr_arg;
-        match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+        self.debug("parse_binary_expression");
+        let mut operands : Vec<Box<AbsExpr>> = vec![self.parse_prefix_expression()?];
+        let mut operators : Vec<(Token, u32)> = Vec::new();
+        while let Some((token, precedence, associativity)) = self.symbol.as_ref().and_then(|symbol| get_binary_operator(symbol.get_token()))
         {
-            Some(Token::MUL) =>
-            {
-                self.skip(Token::MUL)?;
-                let pref_expr = self.parse_prefix_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::MUL,expr,pref_expr));
-                expr = self.parse_multiplicative_expression_rest(abs_bin_expr)?;
-            },
-            Some(Token::DIV) =>
+            // operatorji na skladu, ki vezejo mocneje (ali enako mocno z leve), imajo ze oba operanda
+            while operators.last().is_some_and(|&(_, top)| top > precedence || (top == precedence && associativity == Associativity::Left))
             {
-                self.skip(Token::DIV)?;
-                let pref_expr = self.parse_prefix_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::DIV,expr,pref_expr));
-                expr = self.parse_multiplicative_expression_rest(abs_bin_expr)?;
-            },
-            Some(Token::MOD)  =>
+                reduce_binary_expression(&mut operands, &mut operators);
+            }
+            if operators.last().is_some_and(|&(_, top)| top == precedence)
             {
-                self.skip(Token::MOD)?;
-                let pref_expr = self.parse_prefix_expression()?;
-                let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::MOD,expr,pref_expr));
-                expr = self.parse_multiplicative_expression_rest(abs_bin_expr)?;
-            },
-            _ => {},
+                break;
+            }
+            self.skip(token)?;
+            operators.push((token, precedence));
+            operands.push(self.parse_prefix_expression()?);
+        }
+        while !operators.is_empty()
+        {
+            reduce_binary_expression(&mut operands, &mut operators);
         }
         self.debug_end();
-        Ok(expr)
+        Ok(operands.pop().unwrap())
     }
 
     fn parse_prefix_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
     {
         self.debug("parse_prefix_expression");
-        let expr = match self.symbol.as_ref().map(|symbol| symbol.get_token())
+        let mut operators : Vec<Token> = Vec::new();
+        while let Some(token) = self.symbol.as_ref().map(|symbol| symbol.get_token()).filter(|token| PREFIX_OPERATORS.contains(token))
         {
-            Some(Token::ADD)  => 
-            {
-                self.skip(Token::ADD)?;
-                let expr = self.parse_prefix_expression()?;
-                let abs_un_expr = Box::new(AbsUnExpr::new(AbsUnOper::ADD,expr));
-                abs_un_expr
-            },
-            Some(Token::SUB)  =>
-            {
-                self.skip(Token::SUB)?;
-                let expr = self.parse_prefix_expression()?;
-                let abs_un_expr = Box::new(AbsUnExpr::new(AbsUnOper::SUB,expr));
-                abs_un_expr
-            },
-            Some(Token::MUL) =>
-            {
-                self.skip(Token::MUL)?;
-                let expr = self.parse_prefix_expression()?;
-                let abs_un_expr = Box::new(AbsUnExpr::new(AbsUnOper::MUL,expr));
-                abs_un_expr
-            },
-            Some(Token::AND) =>
-            {
-                self.skip(Token::AND)?;
-                let expr = self.parse_prefix_expression()?;
-                let abs_un_expr = Box::new(AbsUnExpr::new(AbsUnOper::AND,expr));
-                abs_un_expr
-            },
-            Some(Token::NOT) =>
-            {
-                self.skip(Token::NOT)?;
-                let expr = self.parse_prefix_expression()?;
-                let abs_un_expr = Box::new(AbsUnExpr::new(AbsUnOper::NOT,expr));
-                abs_un_expr
-            },
-            _ => {self.parse_postfix_expression()?},
-        };
+            self.skip(token)?;
+            operators.push(token);
+        }
+        let mut expr = self.parse_postfix_expression()?;
+        // najblizji operator se uporabi prvi
+        while let Some(token) = operators.pop()
+        {
+            expr = Box::new(AbsUnExpr::new(get_abs_un_oper(token), expr));
+        }
         self.debug_end();
         Ok(expr)
     }
@@ -369,22 +504,26 @@ impl SyntaxAnalyzer
         {
             Some(Token::INTCONST) => 
             {
-                let atom_expr = Box::new(AbsAtomExpr::new(self.skip(Token::INTCONST)?));
+                let constant = self.skip(Token::INTCONST)?;
+                let atom_expr = self.create_atom_expr(Some(constant))?;
                 self.parse_postfix_expression_rest(atom_expr)?
             },
             Some(Token::REALCONST)  => 
             {
-                let atom_expr = Box::new(AbsAtomExpr::new(self.skip(Token::REALCONST)?));
+                let constant = self.skip(Token::REALCONST)?;
+                let atom_expr = self.create_atom_expr(Some(constant))?;
                 self.parse_postfix_expression_rest(atom_expr)?
             },
             Some(Token::BOOLCONST) => 
             {
-                let atom_expr = Box::new(AbsAtomExpr::new(self.skip(Token::BOOLCONST)?));
+                let constant = self.skip(Token::BOOLCONST)?;
+                let atom_expr = self.create_atom_expr(Some(constant))?;
                 self.parse_postfix_expression_rest(atom_expr)?
             }, 
             Some(Token::STRINGCONST) => 
             {
-                let atom_expr = Box::new(AbsAtomExpr::new(self.skip(Token::STRINGCONST)?));
+                let constant = self.skip(Token::STRINGCONST)?;
+                let atom_expr = self.create_atom_expr(Some(constant))?;
                 self.parse_postfix_expression_rest(atom_expr)?
             },
             Some(Token::IDENTIFIER) => 
@@ -395,14 +534,14 @@ impl SyntaxAnalyzer
                 {
                     Some(Token::LPARENT) => 
                     {
-                        self.skip(Token::LPARENT)?;
+                        let lparent = self.skip(Token::LPARENT)?;
                         let fun_call_params = self.parse_expressions()?;
                         //fun_call_params.calculate_abs_position();
-                        self.skip(Token::RPARENT)?;
+                        self.skip_closing(Token::RPARENT, &lparent)?;
                         let abs_fun_call = Box::new(AbsFunCall::new(abs_expr_name,*fun_call_params));
                         abs_fun_call
                     }
-                    _ => 
+                    _ =>
                     {
                         Box::new(abs_expr_name)
                     },
@@ -411,20 +550,29 @@ impl SyntaxAnalyzer
             },
             Some(Token::LPARENT) =>
             {
-                self.skip(Token::LPARENT)?;
+                let lparent = self.skip(Token::LPARENT)?;
                 let exprs = self.parse_expressions()?;
-                self.skip(Token::RPARENT)?;
+                self.skip_closing(Token::RPARENT, &lparent)?;
                 self.parse_postfix_expression_rest(exprs)?
             },
             Some(Token::LBRACE) => 
             {
-                self.skip(Token::LBRACE)?;
-                let brace_expr = self.parse_postfix_brace_expression()?;
+                let lbrace = self.skip(Token::LBRACE)?;
+                let depth = self.depth;
+                let brace_expr = match self.parse_postfix_brace_expression(&lbrace)
+                {
+                    Ok(brace_expr) => brace_expr,
+                    Err(error) =>
+                    {
+                        self.recover_closing(error, depth, Token::RBRACE)?;
+                        return Err(ParseError::Recovered);
+                    },
+                };
                 self.parse_postfix_expression_rest(brace_expr)?
             }, 
-            _ => 
+            _ =>
             {
-                return  Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take())));
+                return  Err(ParseError::SyntaxError(self.unexpected(&EXPRESSION_FIRST)));
             },
         };
         self.debug_end();
@@ -435,54 +583,112 @@ impl SyntaxAnalyzer
     {
          self.debug("parse_postfix_expression_rest");
          let mut expr = abs_expr_arg;
-         match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+         loop
          {
-             Some(Token::DOT) =>
+             expr = match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
              {
-                 self.skip(Token::DOT)?;
-                 let identifier = Box::new(AbsExprName::new(self.skip(Token::IDENTIFIER)?));
-                 let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::REC,expr,identifier));
-                 expr = self.parse_postfix_expression_rest(abs_bin_expr)?;
-             },
-             Some(Token::LBRACKET) => 
-             {
-                 self.skip(Token::LBRACKET)?;
-                 let offset_expr = self.parse_expression()?;
-                 self.skip(Token::RBRACKET)?;
-                 let abs_bin_expr = Box::new(AbsBinExpr::new(AbsBinOper::ARR,expr,offset_expr));
-                 expr = self.parse_postfix_expression_rest(abs_bin_expr)?;
-             },
-             Some(Token::WHERE) => 
-             {
-                 self.skip(Token::WHERE)?;
-                 let decls = self.parse_declarations()?;
-                 let where_expr = Box::new(AbsWhereExpr::new(expr,decls));
-                 expr = self.parse_postfix_expression_rest(where_expr)?;
-             },
-             _ => {},
+                 Some(Token::DOT) => self.parse_component(expr)?,
+                 Some(Token::LBRACKET) => self.parse_index(expr)?,
+                 Some(Token::WHERE) => 
+                 {
+                     self.skip(Token::WHERE)?;
+                     let decls = self.parse_declarations()?;
+                     Box::new(AbsWhereExpr::new(expr,decls))
+                 },
+                 _ => break,
+             };
          }
          self.debug_end();
          Ok(expr)
     }
 
-     fn parse_postfix_brace_expression(&mut self) -> Result<Box<AbsExpr>,ParseError>
+    // komponenta zapisa: expr . IDENTIFIER
+    fn parse_component(&mut self, expr : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+    {
+        self.skip(Token::DOT)?;
+        let identifier = Box::new(AbsExprName::new(self.skip(Token::IDENTIFIER)?));
+        Ok(Box::new(AbsBinExpr::new(AbsBinOper::REC,expr,identifier)))
+    }
+
+    // element tabele: expr [ expression ]
+    fn parse_index(&mut self, expr : Box<AbsExpr>) -> Result<Box<AbsExpr>,ParseError>
+    {
+        let lbracket = self.skip(Token::LBRACKET)?;
+        let offset_expr = self.parse_expression()?;
+        self.skip_closing(Token::RBRACKET, &lbracket)?;
+        Ok(Box::new(AbsBinExpr::new(AbsBinOper::ARR,expr,offset_expr)))
+    }
+
+    /** Razcleni levo stran prirejanja: ime ali levo stran v oklepajih, ki ji lahko sledijo komponente
+    * (<code>r.f</code>) in indeksi (<code>a[i]</code>), pred njo pa operatorji <code>*</code>.
+    *
+    * Drevo je enako kot pri enakem zapisu v izrazu, zato <code>*p.f</code> pomeni <code>*(p.f)</code>.
+    */
+    fn parse_lvalue(&mut self) -> Result<Box<AbsExpr>,ParseError>
+    {
+        self.enter_nesting("Expression")?;
+        let lvalue = self.parse_lvalue_nested();
+        self.nesting -= 1;
+        lvalue
+    }
+
+    fn parse_lvalue_nested(&mut self) -> Result<Box<AbsExpr>,ParseError>
+    {
+        self.debug("parse_lvalue");
+        let mut dereferences = 0;
+        while self.symbol.as_ref().is_some_and(|symbol| symbol.is_token(Token::MUL))
+        {
+            self.skip(Token::MUL)?;
+            dereferences += 1;
+        }
+        let mut lvalue : Box<AbsExpr> = match self.symbol.as_ref().map(|symbol| symbol.get_token())
+        {
+            Some(Token::IDENTIFIER) => Box::new(AbsExprName::new(self.skip(Token::IDENTIFIER)?)),
+            Some(Token::LPARENT) =>
+            {
+                let lparent = self.skip(Token::LPARENT)?;
+                let inner = self.parse_lvalue()?;
+                self.skip_closing(Token::RPARENT, &lparent)?;
+                let mut exprs = AbsExprs::new_with_exprs(vec![inner]);
+                exprs.calculate_abs_position();
+                Box::new(exprs)
+            },
+            _ => return Err(ParseError::SyntaxError(self.unexpected(&LVALUE_FIRST))),
+        };
+        loop
+        {
+            lvalue = match self.symbol.as_ref().map(|symbol| symbol.get_token())
+            {
+                Some(Token::DOT) => self.parse_component(lvalue)?,
+                Some(Token::LBRACKET) => self.parse_index(lvalue)?,
+                _ => break,
+            };
+        }
+        for _ in 0..dereferences
+        {
+            lvalue = Box::new(AbsUnExpr::new(AbsUnOper::MUL, lvalue));
+        }
+        self.debug_end();
+        Ok(lvalue)
+    }
+
+     fn parse_postfix_brace_expression(&mut self, lbrace : &Symbol) -> Result<Box<AbsExpr>,ParseError>
     {
         self.debug("parse_postfix_brace_expression");
         let expr : Box<AbsExpr> = match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
         {
             Some(Token::RBRACE)  => 
             {
-                self.skip(Token::RBRACE)?;
-                let atom_expr = Box::new(AbsAtomExpr::new_with_option(None));
-                atom_expr
+                self.skip_closing(Token::RBRACE, lbrace)?;
+                self.create_atom_expr(None)?
             },
-            Some(Token::IDENTIFIER) =>
+            Some(Token::IDENTIFIER) | Some(Token::MUL) | Some(Token::LPARENT) =>
             {
-                let expr_name =  Box::new(AbsExprName::new(self.skip(Token::IDENTIFIER)?));
+                let left_expr = self.parse_lvalue()?;
                 self.skip(Token::ASSIGN)?;
                 let right_expr = self.parse_expression()?;
-                let assign_stmt = Box::new(AbsAssignStmt::new(expr_name,right_expr));
-                self.skip(Token::RBRACE)?;
+                let assign_stmt = Box::new(AbsAssignStmt::new(left_expr,right_expr));
+                self.skip_closing(Token::RBRACE, lbrace)?;
                 assign_stmt
             },
             Some(Token::IF) =>
@@ -500,7 +706,7 @@ impl SyntaxAnalyzer
                     },
                     _ => {None},
                 };
-                self.skip(Token::RBRACE)?;
+                self.skip_closing(Token::RBRACE, lbrace)?;
                 let if_stmt = Box::new(AbsIfStmt::new(cond_expr,then_expr,else_expr));
                 if_stmt
             },
@@ -514,7 +720,7 @@ impl SyntaxAnalyzer
                 let higher_bound = self.parse_expression()?;
                 self.skip(Token::COLON)?;
                 let loop_exprs = self.parse_expressions()?;
-                self.skip(Token::RBRACE)?;
+                self.skip_closing(Token::RBRACE, lbrace)?;
                 let for_loop_expr = Box::new(AbsForStmt::new(var_name,lower_bound,higher_bound,loop_exprs));
                 for_loop_expr
             },
@@ -524,13 +730,13 @@ impl SyntaxAnalyzer
                 let cond_expr = self.parse_expression()?;
                 self.skip(Token::COLON)?;
                 let loop_expr = self.parse_expressions()?;
-                self.skip(Token::RBRACE)?;
+                self.skip_closing(Token::RBRACE, lbrace)?;
                 let while_expr = Box::new(AbsWhileStmt::new(cond_expr,loop_expr));
                 while_expr
             },
-            _ => 
+            _ =>
             {
-                return Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take())));
+                return Err(ParseError::SyntaxError(self.unexpected(&BRACE_EXPRESSION_FIRST)));
             },
         };
         self.debug_end();
@@ -541,54 +747,208 @@ impl SyntaxAnalyzer
     {
         self.debug("parse_declarations");
         let mut abs_decls = AbsDecls::new();
-        let decl = self.parse_declaration()?;
-        abs_decls.decls.push(decl);
+        self.parse_declarations_element(&mut abs_decls)?;
         self.parse_declarations_rest(&mut abs_decls)?;
-        abs_decls.calculate_abs_position();
+        if abs_decls.decls.is_empty()
+        {
+            return Err(ParseError::Recovered);
+        }
+        // uvozene deklaracije ne dolocajo polozaja seznama v tej datoteki
+        abs_decls.calculate_abs_position_in_file(self.lexical_analyser.get_file_name_id());
         self.debug_end();
         Ok(abs_decls)
     }
 
     fn parse_declarations_rest(&mut self, abs_decls : &mut AbsDecls) -> Result<(),ParseError>
     {
-        self.debug("parse_declarations_rest");
-        match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+        let mut rests = 0;
+        loop
         {
-            Some(token) if token == Token::TYP || token == Token::FUN || token == Token::VAR =>
+            self.debug("parse_declarations_rest");
+            rests += 1;
+            match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
             {
-                let decl = self.parse_declaration()?;
-                abs_decls.decls.push(decl);
-                self.parse_declarations_rest(abs_decls)?;
+                Some(token) if DECLARATION_FIRST.contains(&token) => self.parse_declarations_element(abs_decls)?,
+                _ =>
+                {
+                    self.expected.extend_from_slice(&DECLARATION_FIRST);
+                    break;
+                },
+            }
+        }
+        self.debug_end_rests(rests);
+        Ok(())
+    }
+
+    /** Razcleni deklaracijo; ob sintaksni napaki jo izpusti in se sinhronizira na naslednjo deklaracijo.  */
+    fn parse_declarations_element(&mut self, abs_decls : &mut AbsDecls) -> Result<(),ParseError>
+    {
+        let depth = self.depth;
+        let result = match self.symbol.as_ref().map(|symbol| symbol.get_token())
+        {
+            Some(Token::IMPORT) => self.parse_import_declaration().map(|(position, decls)|
+            {
+                abs_decls.set_min(&position);
+                abs_decls.set_max(&position);
+                abs_decls.decls.extend(decls);
+            }),
+            _ => self.parse_declaration().map(|decl| abs_decls.decls.push(decl)),
+        };
+        match result
+        {
+            Ok(()) => {},
+            Err(error) =>
+            {
+                self.recover(error, depth, &DECLARATIONS_SYNC)?;
+                if self.symbol.as_ref().is_some_and(|symbol| symbol.is_token(Token::SEMIC))
+                {
+                    self.skip(Token::SEMIC)?;
+                }
             },
-            _ => {}, 
         }
-        self.debug_end();
         Ok(())
     }
 
     fn parse_declaration(&mut self) -> Result<Box<AbsDecl>,ParseError>
     {
         self.debug("parse_declaration");
+        // dokumentacijski komentar je pripet prvemu simbolu deklaracije
+        let doc = self.symbol.as_ref().and_then(|symbol| symbol.get_doc()).map(String::from);
         let decl : Box<AbsDecl> = match self.symbol.as_ref().map(|symbol| symbol.get_token())
         {
             Some(Token::TYP) => 
             {
-                self.parse_type_declaration()?
+                let mut type_decl = self.parse_type_declaration()?;
+                type_decl.doc = doc;
+                type_decl
             },
             Some(Token::FUN)  => 
             {
-                self.parse_function_declaration()?
+                let mut fun_decl = self.parse_function_declaration()?;
+                fun_decl.doc = doc;
+                fun_decl
             }
             Some(Token::VAR) =>
             {
-                self.parse_variable_declaration()?
+                let mut var_decl = self.parse_variable_declaration()?;
+                var_decl.doc = doc;
+                var_decl
             }
-            _ => return Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take()))), 
+            _ => return Err(ParseError::SyntaxError(self.unexpected(&DECLARATION_FIRST))), 
         };
         self.debug_end();
         Ok(decl)
     }
 
+    /** Razcleni deklaracijo <code>import "datoteka";</code> in vrne njen polozaj ter deklaracije uvozene datoteke.
+    *
+    * Napaka pri iskanju ali branju datoteke in krozno uvazanje se zabelezita, deklaracija pa ne prispeva nobene deklaracije.
+    * Vsaka datoteka se uvozi le enkrat: deklaracija, ki ponovno uvozi ze uvozeno datoteko (neposredno ali prek
+    * druge uvozene datoteke), ne prispeva nobene deklaracije.
+    */
+    fn parse_import_declaration(&mut self) -> Result<(Position, Vec<Box<AbsDecl>>),ParseError>
+    {
+        self.debug("parse_import_declaration");
+        let import_symbol = self.skip(Token::IMPORT)?;
+        let file_symbol = self.skip(Token::STRINGCONST)?;
+        let semic_symbol = self.skip(Token::SEMIC)?;
+        let mut position = import_symbol.get_ref_position().clone();
+        position.set_max(semic_symbol.get_ref_position());
+        let decls = match self.import_declarations(&file_symbol)
+        {
+            Ok(decls) => decls,
+            Err(ParseError::IoError(ioe)) => return Err(ParseError::IoError(ioe)),
+            Err(_) => Vec::new(),
+        };
+        self.debug_end();
+        Ok((position, decls))
+    }
+
+    // poisce uvozeno datoteko: najprej glede na direktorij uvazajoce datoteke, nato v direktorijih import_path
+    fn find_import(&self, file : &str) -> Option<PathBuf>
+    {
+        let file = Path::new(file);
+        if file.is_absolute()
+        {
+            return Some(file.to_path_buf()).filter(|path| path.is_file());
+        }
+        let importer_name = self.lexical_analyser.get_file_name();
+        let importer_dir = Path::new(&*importer_name).parent().unwrap_or_else(|| Path::new(""));
+        Some(importer_dir).into_iter().chain(self.import_path.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(file))
+            .find(|path| path.is_file())
+    }
+
+    fn report_import_error(&self, message : &str, file_symbol : &Symbol)
+    {
+        self.diagnostics.push(Diagnostic::error(message, Some(file_symbol.get_ref_position()), ExitCode::SyntaxAnalyzerImportError));
+    }
+
+    /** Prebere in razcleni deklaracije uvozene datoteke z novim sintaksnim analizatorom, ki ima enake nastavitve.
+    *
+    * Obvestila obeh analizatorjev se zbirajo skupaj, drevo izpeljave pa se nadaljuje v istem opazovalcu.
+    * Ce je napaka ze zabelezena, vrne <code>ParseError::Recovered</code>.
+    */
+    fn import_declarations(&mut self, file_symbol : &Symbol) -> Result<Vec<Box<AbsDecl>>,ParseError>
+    {
+        let file = match file_symbol.get_value()
+        {
+            Some(LiteralValue::Str(file)) => file.clone(),
+            _ => return Err(ParseError::Recovered),
+        };
+        let path = match self.find_import(&file)
+        {
+            Some(path) => path,
+            None =>
+            {
+                self.report_import_error(&format!("Cannot find imported file \"{}\"", file), file_symbol);
+                return Err(ParseError::Recovered);
+            },
+        };
+        let name = path.to_string_lossy().into_owned();
+
+        // krozno uvazanje: uvozena datoteka je ze med datotekami, ki uvazajo to datoteko
+        let mut importers = self.importers.clone();
+        if let Ok(importer) = fs::canonicalize(&*self.lexical_analyser.get_file_name())
+        {
+            importers.push((importer, self.lexical_analyser.get_file_name().to_string()));
+        }
+        let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if let Some(index) = importers.iter().position(|(importer, _)| *importer == canonical)
+        {
+            let chain : Vec<&str> = importers[index..].iter().map(|(_, name)| name.as_str()).chain(Some(name.as_str())).collect();
+            self.report_import_error(&format!("Import cycle: {}", chain.join(" -> ")), file_symbol);
+            return Err(ParseError::Recovered);
+        }
+        // datoteka je ze uvozena, njene deklaracije so ze v drevesu
+        if self.imported.borrow().contains(&canonical)
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut lexical_analyser = match LexicalAnalyzer::new_with_diagnostics(name.clone(), self.diagnostics.clone())
+        {
+            Ok(lexical_analyser) => lexical_analyser,
+            Err(e) =>
+            {
+                self.report_import_error(&format!("Cannot read imported file \"{}\": {}", name, e), file_symbol);
+                return Err(ParseError::Recovered);
+            },
+        };
+        lexical_analyser.set_tab_width(self.lexical_analyser.get_tab_width());
+        self.imported.borrow_mut().insert(canonical);
+        let mut syntax_analyzer = SyntaxAnalyzer::new(lexical_analyser);
+        syntax_analyzer.max_nesting_depth = self.max_nesting_depth;
+        syntax_analyzer.nesting = self.nesting;
+        syntax_analyzer.import_path = self.import_path.clone();
+        syntax_analyzer.importers = importers;
+        syntax_analyzer.imported = self.imported.clone();
+        syntax_analyzer.tracer = self.tracer.take();
+        let decls = syntax_analyzer.parse_declarations_fragment();
+        self.tracer = syntax_analyzer.tracer.take();
+        Ok(decls?.map_or_else(Vec::new, |decls| decls.decls))
+    }
+
      fn parse_type_declaration(&mut self) -> Result<Box<AbsTypeDecl>,ParseError>
     {
         self.debug("parse_type_declaration");
@@ -603,6 +963,14 @@ impl SyntaxAnalyzer
     }
 
     fn parse_type(&mut self) -> Result<Box<AbsType>,ParseError>
+    {
+        self.enter_nesting("Type")?;
+        let abs_type = self.parse_type_nested();
+        self.nesting -= 1;
+        abs_type
+    }
+
+    fn parse_type_nested(&mut self) -> Result<Box<AbsType>,ParseError>
     {
         self.debug("parse_type");
         let abs_type : Box<AbsType> = match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
@@ -634,8 +1002,8 @@ impl SyntaxAnalyzer
             Some(Token::LBRACE) => 
             {
                 //returns void type
-                self.skip(Token::LBRACE)?;
-                self.skip(Token::RBRACE)?;
+                let lbrace = self.skip(Token::LBRACE)?;
+                self.skip_closing(Token::RBRACE, &lbrace)?;
                 Box::new(AbsAtomType::new_void_type())
             },
             Some(Token::IDENTIFIER) => 
@@ -654,9 +1022,9 @@ impl SyntaxAnalyzer
             Some(Token::ARR) => 
             {
                 let arr_symbol = self.skip(Token::ARR)?;
-                self.skip(Token::LBRACKET)?;
+                let lbracket = self.skip(Token::LBRACKET)?;
                 let size_expr = self.parse_expression()?;
-                self.skip(Token::RBRACKET)?;
+                self.skip_closing(Token::RBRACKET, &lbracket)?;
                 let arr_type =self.parse_type()?;
                 let array = Box::new(AbsArrType::new(arr_type,size_expr,&arr_symbol));
                 array
@@ -664,22 +1032,22 @@ impl SyntaxAnalyzer
             Some(Token::REC) => 
             {
                 let rec_symbol = self.skip(Token::REC)?;
-                self.skip(Token::LPARENT)?;
+                let lparent = self.skip(Token::LPARENT)?;
                 let record_compoments = self.parse_record_compoments()?;
-                self.skip(Token::RPARENT)?;
+                self.skip_closing(Token::RPARENT, &lparent)?;
                 let mut record = Box::new(AbsRecType::new(record_compoments,&rec_symbol));
                 record
             },
             Some(Token::LPARENT) => 
             {
-                self.skip(Token::LPARENT)?;
+                let lparent = self.skip(Token::LPARENT)?;
                 let abs_type = self.parse_type()?;
-                self.skip(Token::RPARENT)?;
+                self.skip_closing(Token::RPARENT, &lparent)?;
                 abs_type
             },
-            _ => 
+            _ =>
             {
-                 return Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take()))); 
+                 return Err(ParseError::SyntaxError(self.unexpected(&TYPE_FIRST))); 
             },
         };
         self.debug_end();
@@ -689,10 +1057,13 @@ impl SyntaxAnalyzer
     fn parse_record_compoments(&mut self) -> Result<AbsDecls,ParseError>
     {
         self.debug("parse_record_compoments");
-        let compoment = self.parse_record_compoment()?;
         let mut abs_decls = AbsDecls::new();
-        abs_decls.decls.push(compoment);
+        self.parse_record_compoments_element(&mut abs_decls)?;
         self.parse_record_compoments_rest(&mut abs_decls)?;
+        if abs_decls.decls.is_empty()
+        {
+            return Err(ParseError::Recovered);
+        }
         abs_decls.calculate_abs_position();
         self.debug_end();
         Ok(abs_decls)
@@ -700,19 +1071,37 @@ impl SyntaxAnalyzer
 
     fn parse_record_compoments_rest(&mut self, abs_decls : &mut AbsDecls) -> Result<(),ParseError>
     {
-        self.debug("parse_record_compoments_rest");
-        match self.symbol.as_ref().map(|symbol|  symbol.get_token())
+        let mut rests = 0;
+        loop
         {
-            Some(Token::COMMA)  => 
+            self.debug("parse_record_compoments_rest");
+            rests += 1;
+            match self.symbol.as_ref().map(|symbol| symbol.get_token())
             {
-                self.skip(Token::COMMA)?;
-                let compoment = self.parse_record_compoment()?;
-                abs_decls.decls.push(compoment);
-                self.parse_record_compoments_rest(abs_decls)?;
-            },
-            _ => {},
+                Some(Token::COMMA) =>
+                {
+                    self.skip(Token::COMMA)?;
+                    self.parse_record_compoments_element(abs_decls)?;
+                },
+                _ =>
+                {
+                    self.expected.push(Token::COMMA);
+                    break;
+                },
+            }
+        }
+        self.debug_end_rests(rests);
+        Ok(())
+    }
+
+    fn parse_record_compoments_element(&mut self, abs_decls : &mut AbsDecls) -> Result<(),ParseError>
+    {
+        let depth = self.depth;
+        match self.parse_record_compoment()
+        {
+            Ok(compoment) => abs_decls.decls.push(compoment),
+            Err(error) => self.recover(error, depth, &COMPOMENTS_SYNC)?,
         }
-        self.debug_end();
         Ok(())
     }
 
@@ -729,9 +1118,9 @@ impl SyntaxAnalyzer
                 let abs_var_decl = Box::new(AbsVarDecl::new(identifier,abs_type));
                 abs_var_decl
             },
-            _ => 
+            _ =>
             {
-                return Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take())));
+                return Err(ParseError::SyntaxError(self.unexpected(&[Token::IDENTIFIER])));
             },
         };
         self.debug_end();
@@ -743,9 +1132,9 @@ impl SyntaxAnalyzer
         self.debug("parse_function_declaration");
         let fun_symbol = self.skip(Token::FUN)?;
         let fun_name = AbsExprName::new(self.skip(Token::IDENTIFIER)?);
-        self.skip(Token::LPARENT)?;
+        let lparent = self.skip(Token::LPARENT)?;
         let params = self.parse_function_parameters()?;
-        self.skip(Token::RPARENT)?;
+        self.skip_closing(Token::RPARENT, &lparent)?;
         self.skip(Token::COLON)?;
         let return_type = self.parse_type()?;
         self.skip(Token::ASSIGN)?;
@@ -760,14 +1149,28 @@ impl SyntaxAnalyzer
     {
         self.debug("parse_function_parameters");
         let mut params = AbsDecls::new();
-        let arg = self.parse_function_parameter()?;
-        params.decls.push(arg);
+        self.parse_function_parameters_element(&mut params)?;
         self.parse_function_parameters_rest(&mut params)?;
+        if params.decls.is_empty()
+        {
+            return Err(ParseError::Recovered);
+        }
         params.calculate_abs_position();
         self.debug_end();
         Ok(params)
     }
 
+    fn parse_function_parameters_element(&mut self, params : &mut AbsDecls) -> Result<(),ParseError>
+    {
+        let depth = self.depth;
+        match self.parse_function_parameter()
+        {
+            Ok(arg) => params.decls.push(arg),
+            Err(error) => self.recover(error, depth, &COMPOMENTS_SYNC)?,
+        }
+        Ok(())
+    }
+
     fn parse_function_parameter(&mut self) -> Result<Box<AbsVarDecl>,ParseError>
     {
         self.debug("parse_function_parameter");
@@ -781,19 +1184,26 @@ impl SyntaxAnalyzer
 
     fn parse_function_parameters_rest(&mut self, params : &mut AbsDecls) -> Result<(),ParseError>
     {
-        self.debug("parse_function_parameters_rest");
-        match self.symbol.as_ref().map(|symbol| symbol.get_token()) 
+        let mut rests = 0;
+        loop
         {
-            Some(Token::COMMA) => 
+            self.debug("parse_function_parameters_rest");
+            rests += 1;
+            match self.symbol.as_ref().map(|symbol| symbol.get_token())
             {
-                self.skip(Token::COMMA)?;
-                let arg = self.parse_function_parameter()?;
-                params.decls.push(arg);
-                self.parse_function_parameters_rest(params)?;
-            },
-            _ =>  {}, //return Err(ParseError::SyntaxError(SymbolError::new(self.symbol.take()))),
+                Some(Token::COMMA) =>
+                {
+                    self.skip(Token::COMMA)?;
+                    self.parse_function_parameters_element(params)?;
+                },
+                _ =>
+                {
+                    self.expected.push(Token::COMMA);
+                    break;
+                },
+            }
         }
-        self.debug_end();
+        self.debug_end_rests(rests);
         Ok(())
     }
 
@@ -811,46 +1221,197 @@ impl SyntaxAnalyzer
     }
 
 
+    /** Ustvari konstanto ali prazen izraz; ce simbol ni konstanta, zabelezi napako in vrne <code>ParseError::Recovered</code>.  */
+    fn create_atom_expr(&mut self, expr : Option<Symbol>) -> Result<Box<AbsExpr>, ParseError>
+    {
+        match AbsAtomExpr::new_with_option(expr)
+        {
+            Ok(atom_expr) => Ok(Box::new(atom_expr)),
+            Err(diagnostic) =>
+            {
+                self.diagnostics.push(*diagnostic);
+                self.recovered_at = Some(self.consumed);
+                Err(ParseError::Recovered)
+            },
+        }
+    }
+
+    /** Poveca globino gnezdenja izrazov in tipov; ob preseganju najvecje globine zabelezi napako.
+    *
+    * Napaka se vrne kot <code>ParseError::Recovered</code>, zato se analizator sinhronizira sele v seznamu,
+    * ki vsebuje najbolj zunanji izraz. Klicatelj mora ob uspehu globino po koncu produkcije zmanjsati.
+    * @param what Vrsta gnezdene produkcije za sporocilo o napaki.
+    */
+    fn enter_nesting(&mut self, what : &str) -> Result<(), ParseError>
+    {
+        if self.nesting >= self.max_nesting_depth
+        {
+            let position = self.symbol.as_ref().map(|symbol| symbol.get_ref_position());
+            self.diagnostics.push(Diagnostic::error(&format!("{} is nested too deeply (the limit is {} levels)", what, self.max_nesting_depth),
+                                                    position, ExitCode::SyntaxAnalyzerSyntaxError));
+            self.recovered_at = Some(self.consumed);
+            return Err(ParseError::Recovered);
+        }
+        self.nesting += 1;
+        Ok(())
+    }
+
     fn skip(&mut self, token : Token) -> Result<Symbol, ParseError> 
     {
         //let skipped_symbol = self.symbol.take();
-        match self.symbol.take()
+        if !self.symbol.as_ref().is_some_and(|symbol| symbol.is_token(token))
         {
-            None => return Err(ParseError::SyntaxError(SymbolError::new(None))),
-            Some(symbol) =>
+            return Err(ParseError::SyntaxError(self.unexpected(&[token])));
+        }
+        let symbol = self.symbol.take().unwrap();
+        self.trace(|tracer| tracer.consume_symbol(&symbol));
+        self.consumed += 1;
+        self.symbol =  self.next_symbol()?;
+        self.expected.clear();
+        Ok(symbol)
+    }    
+
+    /** Vrne naslednji simbol; simbole <code>ERROR</code> preskoci.
+    *
+    * Leksikalni analizator je napako ze zabelezil, zato sintaksna napaka, ki ji neposredno sledi, ni zabelezena.
+    */
+    fn next_symbol(&mut self) -> Result<Option<Symbol>, ParseError>
+    {
+        loop
+        {
+            match self.lexical_analyser.get_next_symbol()?
             {
-                 if !symbol.is_token(token) { return Err(ParseError::SyntaxError(SymbolError::new(Some(symbol)))); }
-                 self.xml_creator.as_mut().map(|xml| symbol.to_xml(xml));   
-                 self.symbol =  self.lexical_analyser.get_next_symbol()?;
-                 Ok(symbol)
+                Some(ref symbol) if symbol.is_token(Token::ERROR) => self.recovered_at = Some(self.consumed),
+                symbol => return Ok(symbol),
             }
         }
-    }    
+    }
 
-    fn debug(&mut self, nontermial : &str) 
+    /** Vrne napako za trenutni simbol, ki ni med pricakovanimi simboli <code>expected</code>.
+    *
+    * Pricakovanim simbolom doda se simbole, s katerimi bi se lahko nadaljevali seznami, ki so se koncali na trenutnem simbolu.
+    */
+    fn unexpected(&self, expected : &[Token]) -> SymbolError
+    {
+        let mut expected = expected.to_vec();
+        expected.extend_from_slice(&self.expected);
+        SymbolError::new_with_expected(self.symbol.clone(), &expected)
+    }
+
+    /** Zabelezi sintaksno napako in preskoci simbole do prvega simbola iz mnozice <code>sync</code>.
+    *
+    * Napaka se ne zabelezi, ce od zadnje sinhronizacije ni bil prebran noben simbol, saj gre
+    * najverjetneje za posledico prejsnje napake. Leksikalne in vhodno-izhodne napake se ne obravnavajo.
+    * @param depth Globina gnezdenja produkcije, ki napako obravnava.
+    */
+    fn recover(&mut self, error : ParseError, depth : usize, sync : &[Token]) -> Result<(), ParseError>
     {
-        match self.xml_creator 
+        match error
         {
-            Some(ref mut xml) => 
+            ParseError::SyntaxError(syerr) =>
             {
-                writeln!(xml, "<production>").unwrap();
-                writeln!(xml, "<leftside nonterminal=\"{}\"/>", nontermial).unwrap();
-                writeln!(xml, "<rightside>").unwrap();
+                if self.recovered_at != Some(self.consumed)
+                {
+                    self.report_syntax_error(&syerr);
+                }
             },
-            None => {},
+            ParseError::Recovered => {},
+            error => return Err(error),
+        }
+        while self.depth > depth
+        {
+            self.debug_end();
         }
+
+        // oklepaji, ki jih preskocimo, morajo biti zaprti, preden se lahko ustavimo na zaklepaju
+        let mut nesting = 0;
+        loop
+        {
+            match self.symbol.as_ref().map(|symbol| symbol.get_token())
+            {
+                None => break,
+                Some(Token::LPARENT) | Some(Token::LBRACKET) | Some(Token::LBRACE) => nesting += 1,
+                Some(Token::RPARENT) | Some(Token::RBRACKET) | Some(Token::RBRACE) if nesting > 0 => nesting -= 1,
+                Some(token) if nesting == 0 && sync.contains(&token) => break,
+                _ => {},
+            }
+            self.symbol = self.next_symbol()?;
+            self.expected.clear();
+        }
+        self.recovered_at = Some(self.consumed);
+        Ok(())
+    }
+
+    /** Preskoci zaklepaj; ce ga ni, napaki doda oznako pripadajocega oklepaja.
+    *
+    * Po napaki preskoci simbole do pripadajocega zaklepaja. Ce naleti na drug zaklepaj ali
+    * podpicje, se ustavi in vrne <code>ParseError::Recovered</code>.
+    */
+    fn skip_closing(&mut self, token : Token, opening : &Symbol) -> Result<Symbol, ParseError>
+    {
+        match self.skip(token)
+        {
+            Err(ParseError::SyntaxError(syerr)) =>
+            {
+                let syerr = syerr.with_label(opening.get_ref_position(), &format!("to match this `{}`", opening));
+                let depth = self.depth;
+                match self.recover_closing(ParseError::SyntaxError(syerr), depth, token)?
+                {
+                    Some(symbol) => Ok(symbol),
+                    None => Err(ParseError::Recovered),
+                }
+            },
+            result => result,
+        }
+    }
+
+    /** Po napaki znotraj oklepajev preskoci simbole do zaklepaja <code>token</code> in ga preskoci.
+    * @return Preskoceni zaklepaj ali <code>None</code>, ce se je preskakovanje ustavilo na drugem zaklepaju ali podpicju.
+    */
+    fn recover_closing(&mut self, error : ParseError, depth : usize, token : Token) -> Result<Option<Symbol>, ParseError>
+    {
+        self.recover(error, depth, &[token, Token::RPARENT, Token::RBRACKET, Token::RBRACE, Token::SEMIC])?;
+        if self.symbol.as_ref().is_some_and(|symbol| symbol.is_token(token))
+        {
+            Ok(Some(self.skip(token)?))
+        }
+        else
+        {
+            Ok(None)
+        }
+    }
+
+    fn debug(&mut self, nontermial : &str) 
+    {
+        self.depth += 1;
+        self.trace(|tracer| tracer.enter_production(nontermial));
     }
     
     fn debug_end(&mut self)
     {
-        match self.xml_creator 
+        self.depth -= 1;
+        self.trace(|tracer| tracer.exit_production());
+    }
+
+    // konca produkcije seznama, ki se bere v zanki, tako kot bi se koncale v rekurziji (dolg seznam ne poglablja sklada)
+    fn debug_end_rests(&mut self, rests : usize)
+    {
+        for _ in 0..rests
         {
-            Some(ref mut xml) => 
+            self.debug_end();
+        }
+    }
+
+    // posreduje dogodek opazovalcu; ob napaki pri pisanju opazovalca zavrze, napako pa vrne parse
+    fn trace<F>(&mut self, event : F) where F : FnOnce(&mut ParseTracer) -> io::Result<()>
+    {
+        if let Some(ref mut tracer) = self.tracer
+        {
+            if let Err(ioe) = event(tracer.as_mut())
             {
-                writeln!(xml, "</rightside>").unwrap();
-                writeln!(xml, "</production>").unwrap();
-            },
-            None => {},
+                self.tracer = None;
+                self.trace_error = Some(ioe);
+            }
         }
     }
 
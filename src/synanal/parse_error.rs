@@ -1,4 +1,7 @@
-use lexanal::symbol::Symbol;
+use lexanal::symbol::{Symbol, Token};
+use lexanal::lex_error::LexError;
+use report::Label;
+use lexanal::position::Position;
 use std::io;
 use std::error;
 use std::fmt::Display;
@@ -7,50 +10,100 @@ use std::fmt;
 #[derive(Debug)]
 pub struct SymbolError
 {
-    symbol : Option<Symbol>,
-    description : String,
+    // simbol je v skatli, da napaka (in s tem ParseError) ostane majhna
+    symbol : Option<Box<Symbol>>,
+    expected : Vec<Token>,
+    labels : Vec<Label>,
 }
 
 impl SymbolError
 {
     pub fn new(symbol : Option<Symbol>) -> SymbolError
     {
-         SymbolError
+        SymbolError::new_with_expected(symbol, &[])
+    }
+
+    /** Ustvari napako, ki poleg nepricakovanega simbola hrani tudi vrste simbolov, ki bi jih sintaksni analizator sprejel.
+    * @param symbol Nepricakovan simbol ali <code>None</code> ob koncu datoteke.
+    * @param expected Vrste simbolov, ki bi jih sintaksni analizator na tem mestu sprejel.
+    */
+    pub fn new_with_expected(symbol : Option<Symbol>, expected : &[Token]) -> SymbolError
+    {
+        let mut expected_tokens : Vec<Token> = Vec::new();
+        for token in expected
+        {
+            if !expected_tokens.contains(token)
+            {
+                expected_tokens.push(*token);
+            }
+        }
+        SymbolError
+        {
+            symbol : symbol.map(Box::new),
+            expected : expected_tokens,
+            labels : Vec::new(),
+        }
+    }
+
+    // npr. "expected `then` or `,`, found `{`"
+    fn describe(&self) -> String
+    {
+        let found = match self.symbol
+        {
+            Some(ref symbol) => match symbol.get_token()
+            {
+                Token::IDENTIFIER | Token::INTCONST | Token::REALCONST | Token::BOOLCONST | Token::STRINGCONST =>
+                    format!("{} `{}`", symbol.get_token(), symbol.get_ref_lexeme()),
+                token => format!("{}", token),
+            },
+            None => format!("{}", Token::EOF),
+        };
+        match self.expected.split_last()
         {
-            description : match symbol 
+            None => format!("unexpected {}", found),
+            Some((last, [])) => format!("expected {}, found {}", last, found),
+            Some((last, others)) =>
             {
-                Some(ref s) => format!("Invalid symbol {} in {}.", s, s.get_position()),
-                None => format!("End of stream error!"),
+                let others : Vec<String> = others.iter().map(|token| token.to_string()).collect();
+                format!("expected {} or {}, found {}", others.join(", "), last, found)
             },
-            symbol : symbol,
         }
-     } 
+    }
+
+     /** Napaki doda oznako, ki opozori na drug del vhodne datoteke (npr. na pripadajoci oklepaj).  */
+     pub fn with_label(mut self, position : &Position, msg : &str) -> SymbolError
+     {
+         self.labels.push(Label { position : position.clone(), message : msg.to_string() });
+         self
+     }
+
+     pub fn get_labels(&self) -> &[Label]
+     {
+         &self.labels
+     }
 
      pub fn get_ref_symbol(&self)  -> Option<&Symbol>
      {
-         self.symbol.as_ref()
+         self.symbol.as_deref()
+     }
+
+     /** Vrne vrste simbolov, ki bi jih sintaksni analizator na mestu napake sprejel (prazno, ce niso znane).  */
+     pub fn get_expected(&self) -> &[Token]
+     {
+         &self.expected
      }
 }
 
 
 impl error::Error for SymbolError
 {
-    fn description(&self) -> &str 
-    {
-        &self.description
-    }    
-
 }
 
 impl Display for SymbolError 
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result 
     {
-        match self.symbol 
-        {
-            Some(ref symbol) => write!(f, "Symbol error:  {:?}", symbol.get_token()),
-            None => write!(f, "Unexpected end of file."),
-        }      
+        write!(f, "{}", self.describe())
     }
 }
 
@@ -61,6 +114,8 @@ impl Display for SymbolError
 pub enum ParseError
 {
     SyntaxError(SymbolError),
+    // sintaksna napaka je ze zabelezena, sintaksni analizator pa se je sinhroniziral
+    Recovered,
     IoError(io::Error),
 }
 
@@ -71,6 +126,7 @@ impl Display for ParseError
         match *self 
         {
             ParseError::SyntaxError(ref synerr) =>  write!(f, "Syntax error: {}", synerr),
+            ParseError::Recovered => write!(f, "Recovered from syntax error"),
             ParseError::IoError(ref ioe) => write!(f,"IO error: {}", ioe),
         }
     }
@@ -83,7 +139,8 @@ impl error::Error for ParseError
     {
         match *self 
         {
-            ParseError::SyntaxError(ref synerr) => synerr.description(),
+            ParseError::SyntaxError(_) => "Syntax error",
+            ParseError::Recovered => "Recovered from syntax error",
             ParseError::IoError(ref ioe) => ioe.description(),
         }
     }
@@ -93,6 +150,7 @@ impl error::Error for ParseError
           match *self
         {
             ParseError::SyntaxError(ref synerr) => synerr.cause(),
+            ParseError::Recovered => None,
             ParseError::IoError(ref ioe) => ioe.cause(),
         }
     }
@@ -106,6 +164,17 @@ impl From<io::Error> for ParseError {
     }
 }
 
+impl From<LexError> for ParseError
+{
+    fn from(err : LexError) -> ParseError
+    {
+        match err
+        {
+            LexError::IoError(ioe) => ParseError::IoError(ioe),
+        }
+    }
+}
+
 impl From<SymbolError> for ParseError 
 {
     fn from(err : SymbolError) -> ParseError
@@ -1,50 +1,83 @@
-use lexanal::LexicalAnalyzer;
-use synanal::SyntaxAnalyzer;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use synanal::trace::{ParseTracer, TraceFormat, XmlTracer, TextTracer, JsonTracer};
 use xml::ProteusXmlCreator;
 use abstree::print_xml::AbsTreeXmlPrinter;
-use abstree::{AbsTree};
-use abstree::visitor::Visitor;
-use std::str::FromStr;
-use std;
+use compiler::{self, CompileOptions, Phase};
+use driver::{self, Options};
 
 
-pub fn run(program_name : String)
+/** Odpre datoteko z drevesom izpeljave v izbrani obliki.  */
+fn open_tracer(format : TraceFormat, options : &Options) -> io::Result<Box<ParseTracer>>
 {
-    std::env::set_var("PROTEUSXSL",std::env::current_dir().unwrap().join("xsl\\"));
-    let mut abstree_xml_creator : ProteusXmlCreator = match ProteusXmlCreator::open(String::from_str("abstree").unwrap()) {
-        Ok(xml_creator) => xml_creator,
-        Err(e) =>
+    match format
+    {
+        TraceFormat::Xml => ProteusXmlCreator::open_in_dir(Phase::Synanal.get_name().to_string(), &options.output_dir, options.get_xsl_dir())
+                                .map(|xml_creator| Box::new(XmlTracer::new(xml_creator)) as Box<ParseTracer>),
+        TraceFormat::Text => File::create(options.get_trace_path().unwrap())
+                                 .map(|file| Box::new(TextTracer::new(Box::new(BufWriter::new(file)))) as Box<ParseTracer>),
+        TraceFormat::Json => File::create(options.get_trace_path().unwrap())
+                                 .map(|file| Box::new(JsonTracer::new(Box::new(BufWriter::new(file)))) as Box<ParseTracer>),
+    }
+}
+
+pub fn run(options : &Options) -> i32
+{
+    // faza abstree izpise abstraktno sintaksno drevo, drevo izpeljave pa le, ce je izbrana njegova oblika
+    let abstree_xml_creator = match options.phase
+    {
+        Phase::Abstree => match ProteusXmlCreator::open_in_dir(options.phase.get_name().to_string(), &options.output_dir, options.get_xsl_dir())
         {
-             println!("{}",e);
-             std::process::exit(-2);
+            Ok(xml_creator) => Some(xml_creator),
+            Err(e) =>
+            {
+                 eprintln!(":-( {}",e);
+                 return driver::EXIT_IO_ERROR;
+            },
         },
+        _ => None,
     };
-    let mut lexical_analyser = match LexicalAnalyzer::new(program_name) 
+    let tracer = match options.trace.map(|format| open_tracer(format, options))
     {
-        Ok(lexical_analyser) => lexical_analyser,
-        Err(e) =>
+        Some(Ok(tracer)) => Some(tracer),
+        Some(Err(e)) =>
         {
-             println!("{}",e);
-             std::process::exit(-1);
-        }
+             eprintln!(":-( {}",e);
+             return driver::EXIT_IO_ERROR;
+        },
+        None => None,
     };
-
-    let  mut syntax_analyzer = SyntaxAnalyzer::new(lexical_analyser);
-    let abstree = syntax_analyzer.parse().map_err(|error| {
-        println!("Error while opening file: {}", error);
-        std::process::exit(-3);
-    }).unwrap();
-
-    let abstree : Box<AbsTree> = match abstree
+    let compile_options = CompileOptions::from(options);
+    let result = match tracer
+    {
+        Some(tracer) => compiler::compile_with_tracer(&options.program_name, &compile_options, tracer),
+        None => compiler::compile(&options.program_name, &compile_options),
+    };
+    let result = match result
     {
-        Some(abstree) => abstree,
-        None => 
+        Ok(result) => result,
+        Err(e) =>
         {
-            println!("Error: Empty file!");
-            std::process::exit(-2);
+            eprintln!(":-( {}", e);
+            return driver::EXIT_IO_ERROR;
         },
     };
 
-    let mut print_abs_xml : AbsTreeXmlPrinter = AbsTreeXmlPrinter::new(abstree_xml_creator);
-    abstree.accept(&mut print_abs_xml);
-}
\ No newline at end of file
+    let status = driver::emit_diagnostics(&result, options);
+    if status != driver::EXIT_SUCCESS
+    {
+        return status;
+    }
+
+    // prazen program nima drevesa; tako kot pri compile_str se prevajanje uspesno konca, abstree.xml pa ostane prazen
+    if let (Some(abstree), Some(abstree_xml_creator)) = (result.abstree, abstree_xml_creator)
+    {
+        let mut print_abs_xml : AbsTreeXmlPrinter = AbsTreeXmlPrinter::new(abstree_xml_creator);
+        abstree.accept(&mut print_abs_xml);
+    }
+    if options.is_verbose()
+    {
+        eprintln!("{}: syntax analysis finished", options.phase);
+    }
+    driver::EXIT_SUCCESS
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use lexanal::dump;
+use lexanal::symbol::Symbol;
+use xml::{ProteusXmlCreator, XMLable};
+
+
+/** Opazovalec sintaksne analize, ki zapisuje drevo izpeljave.
+ *
+ * Sintaksni analizator ob zacetku vsake produkcije poklice <code>enter_production</code>, ob njenem koncu
+ * <code>exit_production</code>, vmes pa za vsak prebrani simbol <code>consume_symbol</code>. Tudi po sintaksni
+ * napaki so klici uravnotezeni: produkcije, ki jih napaka prekine, se zakljucijo pred sinhronizacijo.
+ */
+pub trait ParseTracer
+{
+    fn enter_production(&mut self, nonterminal : &str) -> io::Result<()>;
+    fn exit_production(&mut self) -> io::Result<()>;
+    fn consume_symbol(&mut self, symbol : &Symbol) -> io::Result<()>;
+}
+
+/** Oblika zapisa drevesa izpeljave. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TraceFormat
+{
+    // synanal.xml, kot ga prikaze xsl/synanal.xsl
+    Xml,
+    // produkcije in simboli, zamaknjeni glede na globino
+    Text,
+    // en JSON objekt z gnezdenimi produkcijami
+    Json,
+}
+
+impl TraceFormat
+{
+    /** Vrne koncnico izhodne datoteke. */
+    pub fn get_extension(&self) -> &'static str
+    {
+        match *self
+        {
+            TraceFormat::Xml => "xml",
+            TraceFormat::Text => "txt",
+            TraceFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for TraceFormat
+{
+    type Err = String;
+
+    fn from_str(format : &str) -> Result<TraceFormat, String>
+    {
+        match format
+        {
+            "xml" => Ok(TraceFormat::Xml),
+            "text" => Ok(TraceFormat::Text),
+            "json" => Ok(TraceFormat::Json),
+            _ => Err(format!("Unknown trace format '{}'", format)),
+        }
+    }
+}
+
+impl fmt::Display for TraceFormat
+{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            TraceFormat::Xml => write!(f, "xml"),
+            TraceFormat::Text => write!(f, "text"),
+            TraceFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/** Zapisuje drevo izpeljave v XML obliki (elementi <code>production</code>, <code>leftside</code> in <code>rightside</code>). */
+pub struct XmlTracer
+{
+    xml_creator : ProteusXmlCreator,
+}
+
+impl XmlTracer
+{
+    pub fn new(xml_creator : ProteusXmlCreator) -> XmlTracer
+    {
+        XmlTracer { xml_creator }
+    }
+}
+
+impl ParseTracer for XmlTracer
+{
+    fn enter_production(&mut self, nonterminal : &str) -> io::Result<()>
+    {
+        writeln!(self.xml_creator, "<production>")?;
+        writeln!(self.xml_creator, "<leftside nonterminal=\"{}\"/>", nonterminal)?;
+        writeln!(self.xml_creator, "<rightside>")
+    }
+
+    fn exit_production(&mut self) -> io::Result<()>
+    {
+        writeln!(self.xml_creator, "</rightside>")?;
+        writeln!(self.xml_creator, "</production>")
+    }
+
+    fn consume_symbol(&mut self, symbol : &Symbol) -> io::Result<()>
+    {
+        symbol.to_xml(&mut self.xml_creator);
+        Ok(())
+    }
+}
+
+/** Zapisuje drevo izpeljave kot besedilo: vsaka produkcija in vsak simbol (glej <code>dump::symbol_to_text</code>)
+ * sta v svoji vrstici, zamaknjeni za dva presledka na vsako raven gnezdenja.
+ */
+pub struct TextTracer
+{
+    out : Box<Write>,
+    depth : usize,
+}
+
+impl TextTracer
+{
+    pub fn new(out : Box<Write>) -> TextTracer
+    {
+        TextTracer { out, depth : 0 }
+    }
+}
+
+impl ParseTracer for TextTracer
+{
+    fn enter_production(&mut self, nonterminal : &str) -> io::Result<()>
+    {
+        writeln!(self.out, "{:indent$}{}", "", nonterminal, indent = 2 * self.depth)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn exit_production(&mut self) -> io::Result<()>
+    {
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn consume_symbol(&mut self, symbol : &Symbol) -> io::Result<()>
+    {
+        writeln!(self.out, "{:indent$}{}", "", dump::symbol_to_text(symbol), indent = 2 * self.depth)
+    }
+}
+
+/** Zapisuje drevo izpeljave kot JSON objekt oblike <code>{"nonterminal":...,"children":[...]}</code>;
+ * otroci so produkcije in simboli (glej <code>dump::symbol_to_json</code>).
+ */
+pub struct JsonTracer
+{
+    out : Box<Write>,
+    // za vsako odprto produkcijo: ali ze ima kaksnega otroka
+    has_children : Vec<bool>,
+}
+
+impl JsonTracer
+{
+    pub fn new(out : Box<Write>) -> JsonTracer
+    {
+        JsonTracer { out, has_children : Vec::new() }
+    }
+
+    // loci otroka od prejsnjega otroka iste produkcije
+    fn write_separator(&mut self) -> io::Result<()>
+    {
+        if let Some(has_children) = self.has_children.last_mut()
+        {
+            if *has_children
+            {
+                write!(self.out, ",")?;
+            }
+            *has_children = true;
+        }
+        Ok(())
+    }
+}
+
+impl ParseTracer for JsonTracer
+{
+    fn enter_production(&mut self, nonterminal : &str) -> io::Result<()>
+    {
+        self.write_separator()?;
+        write!(self.out, "{{\"nonterminal\":{},\"children\":[", dump::str_to_json(nonterminal))?;
+        self.has_children.push(false);
+        Ok(())
+    }
+
+    fn exit_production(&mut self) -> io::Result<()>
+    {
+        self.has_children.pop();
+        write!(self.out, "]}}")?;
+        if self.has_children.is_empty()
+        {
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+
+    fn consume_symbol(&mut self, symbol : &Symbol) -> io::Result<()>
+    {
+        self.write_separator()?;
+        write!(self.out, "{}", dump::symbol_to_json(symbol))
+    }
+}
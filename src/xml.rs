@@ -2,6 +2,7 @@ use std::io::{Write,BufWriter};
 use std::fs::File;
 use std::io;
 use std::env;
+use std::path::{Path, PathBuf};
 
  /**
 	 * Izpise objekt v XML obliki na izhodni tok.
@@ -41,18 +42,29 @@ impl ProteusXmlCreator {
         */
     pub fn open(phase : String)   -> io::Result<ProteusXmlCreator>  //-> io::Result<BufWriter> 
     {
-        let file = File::create(format!("{}.xml", phase))?;
+        let xsl_dir = env::var_os("PROTEUSXSL").map(PathBuf::from);
+        Self::open_in_dir(phase, Path::new("."), xsl_dir.as_deref())
+    }
+
+    /** Odpre izhodno datoteko <tt>phase.xml</tt> v podanem direktoriju.
+    * @param phase Ime faze prevajanja.
+    * @param output_dir Direktorij, v katerega se zapise izhodna datoteka.
+    * @param xsl_dir Direktorij s pripadajocimi <tt>.xsl</tt> datotekami ali None, ce se referenca ne izpise.
+    */
+    pub fn open_in_dir(phase : String, output_dir : &Path, xsl_dir : Option<&Path>) -> io::Result<ProteusXmlCreator>
+    {
+        let file = File::create(output_dir.join(format!("{}.xml", phase)))?;
         let writer = BufWriter::new(file);  
 
         let mut xml_creator = ProteusXmlCreator {phase, writer};
 
-        writeln!(xml_creator.writer, "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>")?;
-        match env::var("PROTEUSXSL") 
+        writeln!(xml_creator.writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        if let Some(xsl_dir) = xsl_dir
         {
             // "<?xml-stylesheet type=\"text/xsl\" href=\"" + xslDir + "/" + phase + ".xsl\"?>"
-            Ok(xsl_dir) => writeln!(xml_creator.writer, "<?xml-stylesheet type=\"text/xsl\" href=\"{}{}.xsl\"?>",xsl_dir,xml_creator.phase)?,
-            Err(_) => println!("ProteusXmlCreator::open -> Error: Could not write!"),
-        };
+            let href = xsl_dir.join(format!("{}.xsl", xml_creator.phase));
+            writeln!(xml_creator.writer, "<?xml-stylesheet type=\"text/xsl\" href=\"{}\"?>", href.display())?;
+        }
             
         writeln!(xml_creator.writer, "<{}>",xml_creator.phase)?;
 
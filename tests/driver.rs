@@ -0,0 +1,67 @@
+extern crate proteus_compiler;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use proteus_compiler::CompileOptions;
+use proteus_compiler::compiler::compile_str;
+use proteus_compiler::driver::{self, Options};
+
+
+// ustvari prazen direktorij z datoteko main.proteus s podano vsebino
+fn create_program(test : &str, source : &str) -> PathBuf
+{
+    let dir = env::temp_dir().join(format!("proteus_driver_{}_{}", process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("main.proteus"), source).unwrap();
+    dir
+}
+
+// prevede main.proteus do podane faze in vrne izhodni status ter vsebino izhodne datoteke
+fn run_phase(dir : &Path, phase : &str) -> (i32, String)
+{
+    let args = vec![dir.join("main.proteus").to_string_lossy().into_owned(), String::from("--phase"), phase.to_string(),
+                    String::from("--output-dir"), dir.to_string_lossy().into_owned(), String::from("--quiet")];
+    let options = Options::from_args(args).unwrap();
+    let status = driver::run(&options);
+    let output = fs::read_to_string(options.get_output_path()).unwrap_or_default();
+    (status, output)
+}
+
+#[test]
+fn empty_program_compiles_like_compile_str()
+{
+    let result = compile_str("empty", "", &CompileOptions::default()).unwrap();
+    assert!(!result.has_errors());
+    assert!(result.abstree.is_none());
+
+    let dir = create_program("empty", "");
+    for &phase in ["lexanal", "synanal", "abstree"].iter()
+    {
+        let (status, output) = run_phase(&dir, phase);
+        assert_eq!(status, driver::EXIT_SUCCESS, "phase {}", phase);
+        assert!(output.ends_with(&format!("<{}>\n</{}>\n", phase, phase)), "phase {}: {}", phase, output);
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn xml_output_refers_to_the_given_xsl_directory()
+{
+    let dir = create_program("xsl", "x where var x:int;");
+    let program = dir.join("main.proteus").to_string_lossy().into_owned();
+    let mut options = Options::from_args(vec![program, String::from("--output-dir"), dir.to_string_lossy().into_owned(),
+                                              String::from("--quiet")]).unwrap();
+    options.xsl_dir = Some(PathBuf::from("styles"));
+    assert_eq!(driver::run(&options), driver::EXIT_SUCCESS);
+    let output = fs::read_to_string(options.get_output_path()).unwrap();
+    assert!(output.contains("href=\"styles/abstree.xsl\""), "{}", output);
+
+    options.xsl_dir = None;
+    assert_eq!(driver::run(&options), driver::EXIT_SUCCESS);
+    let output = fs::read_to_string(options.get_output_path()).unwrap();
+    assert!(!output.contains("xml-stylesheet"), "{}", output);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,157 @@
+extern crate proteus_compiler;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::rc::Rc;
+use proteus_compiler::CompileOptions;
+use proteus_compiler::compiler::{compile_with_tracer, CompilationResult};
+use proteus_compiler::lexanal::symbol::Symbol;
+use proteus_compiler::synanal::trace::ParseTracer;
+
+
+// zabelezi besedila vseh prebranih simbolov, tudi simbolov uvozenih datotek
+struct SymbolRecorder
+{
+    lexemes : Rc<RefCell<Vec<String>>>,
+}
+
+impl ParseTracer for SymbolRecorder
+{
+    fn enter_production(&mut self, _nonterminal : &str) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn exit_production(&mut self) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn consume_symbol(&mut self, symbol : &Symbol) -> io::Result<()>
+    {
+        self.lexemes.borrow_mut().push(symbol.get_ref_lexeme().to_string());
+        Ok(())
+    }
+}
+
+// ustvari prazen direktorij z datotekami, podanimi kot (relativna pot, vsebina)
+fn create_files(test : &str, files : &[(&str, &str)]) -> PathBuf
+{
+    let dir = env::temp_dir().join(format!("proteus_imports_{}_{}", process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    for &(name, source) in files
+    {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    dir
+}
+
+// prevede main.proteus in vrne rezultat ter besedila vseh razclenjenih simbolov
+fn compile_main(dir : &PathBuf, import_path : Vec<PathBuf>) -> (CompilationResult, Vec<String>)
+{
+    let lexemes = Rc::new(RefCell::new(Vec::new()));
+    let options = CompileOptions { import_path, ..CompileOptions::default() };
+    let main = dir.join("main.proteus");
+    let result = compile_with_tracer(main.to_str().unwrap(), &options, Box::new(SymbolRecorder { lexemes : lexemes.clone() })).unwrap();
+    let _ = fs::remove_dir_all(dir);
+    let lexemes = lexemes.borrow().clone();
+    (result, lexemes)
+}
+
+fn get_error_messages(result : &CompilationResult) -> Vec<String>
+{
+    result.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).map(|diagnostic| diagnostic.message.clone()).collect()
+}
+
+fn count(lexemes : &[String], lexeme : &str) -> usize
+{
+    lexemes.iter().filter(|&other| other == lexeme).count()
+}
+
+#[test]
+fn imported_declarations_are_parsed()
+{
+    let dir = create_files("parsed", &[("main.proteus", "x where import \"lib/a.proteus\"; var x:int;"),
+                                       ("lib/a.proteus", "var from_a:int; typ t:int;")]);
+    let (result, lexemes) = compile_main(&dir, Vec::new());
+    assert_eq!(get_error_messages(&result), Vec::<String>::new());
+    assert!(result.abstree.is_some());
+    assert_eq!(count(&lexemes, "from_a"), 1);
+}
+
+#[test]
+fn import_cycle_is_reported()
+{
+    let dir = create_files("cycle", &[("main.proteus", "x where import \"a.proteus\"; var x:int;"),
+                                      ("a.proteus", "import \"b.proteus\"; var from_a:int;"),
+                                      ("b.proteus", "import \"a.proteus\"; var from_b:int;")]);
+    let (result, lexemes) = compile_main(&dir, Vec::new());
+    let messages = get_error_messages(&result);
+    let file = |name : &str| dir.join(name).to_string_lossy().into_owned();
+    assert_eq!(messages, [format!("Import cycle: {} -> {} -> {}", file("a.proteus"), file("b.proteus"), file("a.proteus"))]);
+    assert_eq!(count(&lexemes, "from_a"), 1);
+    assert_eq!(count(&lexemes, "from_b"), 1);
+
+    let dir = create_files("self_import", &[("main.proteus", "x where import \"main.proteus\"; var x:int;")]);
+    let (result, _) = compile_main(&dir, Vec::new());
+    let messages = get_error_messages(&result);
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(messages[0].starts_with("Import cycle: "), "{}", messages[0]);
+}
+
+#[test]
+fn missing_import_is_reported()
+{
+    let dir = create_files("missing", &[("main.proteus", "x where import \"nope.proteus\"; var x:int;")]);
+    let (result, _) = compile_main(&dir, Vec::new());
+    assert_eq!(get_error_messages(&result), ["Cannot find imported file \"nope.proteus\""]);
+    assert!(result.abstree.is_some());
+}
+
+#[test]
+fn imports_are_found_on_the_import_path()
+{
+    let dir = create_files("search_path", &[("src/main.proteus", "x where import \"lib.proteus\"; import \"local.proteus\"; var x:int;"),
+                                            ("src/local.proteus", "var from_local:int;"),
+                                            ("first/lib.proteus", "var from_first:int;"),
+                                            ("second/lib.proteus", "var from_second:int;"),
+                                            ("second/local.proteus", "var from_second_local:int;")]);
+    let (result, lexemes) = compile_main(&dir.join("src"), vec![dir.join("first"), dir.join("second")]);
+    let _ = fs::remove_dir_all(&dir);
+    assert_eq!(get_error_messages(&result), Vec::<String>::new());
+    // direktorij uvazajoce datoteke ima prednost, nato se direktoriji pregledajo po vrsti
+    assert_eq!(count(&lexemes, "from_local"), 1);
+    assert_eq!(count(&lexemes, "from_second_local"), 0);
+    assert_eq!(count(&lexemes, "from_first"), 1);
+    assert_eq!(count(&lexemes, "from_second"), 0);
+}
+
+#[test]
+fn duplicate_imports_are_parsed_once()
+{
+    let dir = create_files("duplicate", &[("main.proteus", "x where import \"a.proteus\"; import \"./a.proteus\"; var x:int; import \"a.proteus\";"),
+                                          ("a.proteus", "var from_a:int;")]);
+    let (result, lexemes) = compile_main(&dir, Vec::new());
+    assert_eq!(get_error_messages(&result), Vec::<String>::new());
+    assert_eq!(count(&lexemes, "from_a"), 1);
+}
+
+#[test]
+fn diamond_imports_are_parsed_once()
+{
+    let dir = create_files("diamond", &[("main.proteus", "x where import \"left.proteus\"; import \"right.proteus\"; var x:int;"),
+                                        ("left.proteus", "import \"base.proteus\"; var from_left:int;"),
+                                        ("right.proteus", "import \"base.proteus\"; var from_right:int;"),
+                                        ("base.proteus", "var from_base:int;")]);
+    let (result, lexemes) = compile_main(&dir, Vec::new());
+    assert_eq!(get_error_messages(&result), Vec::<String>::new());
+    assert_eq!(count(&lexemes, "from_base"), 1);
+    assert_eq!(count(&lexemes, "from_left"), 1);
+    assert_eq!(count(&lexemes, "from_right"), 1);
+}
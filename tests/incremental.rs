@@ -0,0 +1,141 @@
+extern crate proteus_compiler;
+
+use std::cmp;
+use proteus_compiler::lexanal::DEFAULT_TAB_WIDTH;
+use proteus_compiler::lexanal::incremental::{LexedDocument, TextEdit};
+
+
+const SOURCE : &str = "## doc\r\nfun f(a:int):int = a + 1 # comment\r\n\
+                       var s : string; #{ block #{ nested }#\n still }# var x:real;\n\
+                       typ t : arr[10] rec(x:int, y:bool);\r\n\
+                       fun g(b:bool):string = { if b then \"yes\tno\" else \"\u{0161}\u{010D}\" }\n\
+                       var n : int = 0x1F + 1_000 + 2.5e3;\t# trailing\n";
+
+// besedila, ki se vstavljajo; med njimi so zacetki in konci nizov ter komentarjev, ki spremenijo ostanek datoteke
+const INSERTIONS : &[&str] = &["", "x", "1", "\"", "#", "#{", "}#", "##", "\n", "\r\n", " ", "\t", "fun", "==", "<",
+                               "=", "_a9", ".", "0x", "e-", "\u{017E}", "$", "a b", "\"s\" # c\n"];
+
+// opis dokumenta, ki vsebuje vse simbole, polozaje, presledke, komentarje in leksikalne napake
+fn describe(document : &LexedDocument) -> String
+{
+    format!("{:?}\n{:?}\n{:?}", document.get_symbols(), document.get_end_trivia(), document.get_diagnostics())
+}
+
+// spremeni dokument in preveri, da je enak dokumentu, ki je v celoti ponovno prebran
+fn check_edit(document : &mut LexedDocument, edit : &TextEdit, lossless : bool)
+{
+    let mut source = document.get_source().to_string();
+    source.replace_range(edit.begin_offset..edit.end_offset, &edit.text);
+    document.apply_edit(edit).unwrap();
+    assert_eq!(document.get_source(), source);
+    let relexed = LexedDocument::new_with_options(String::from("test"), source, DEFAULT_TAB_WIDTH, lossless);
+    assert_eq!(describe(document), describe(&relexed), "edit {:?}", edit);
+}
+
+// zacetki in konci vseh simbolov ter odmiki znotraj njih
+fn get_offsets(document : &LexedDocument) -> (Vec<usize>, Vec<usize>)
+{
+    let source = document.get_source();
+    let mut boundaries = Vec::new();
+    let mut inside = Vec::new();
+    for symbol in document.get_symbols()
+    {
+        let position = symbol.get_ref_position();
+        boundaries.push(position.get_begin_offset());
+        boundaries.push(position.get_end_offset());
+        inside.extend((position.get_begin_offset() + 1..position.get_end_offset()).filter(|&offset| source.is_char_boundary(offset)));
+    }
+    (boundaries, inside)
+}
+
+#[test]
+fn edits_at_token_boundaries_match_full_relex()
+{
+    for &lossless in [false, true].iter()
+    {
+        let document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+        let (boundaries, _) = get_offsets(&document);
+        for &offset in boundaries.iter()
+        {
+            for text in INSERTIONS.iter()
+            {
+                let mut document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+                check_edit(&mut document, &TextEdit::new(offset, offset, text), lossless);
+            }
+        }
+        // brisanje celih simbolov
+        for symbol in document.get_symbols()
+        {
+            let position = symbol.get_ref_position();
+            let mut document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+            check_edit(&mut document, &TextEdit::new(position.get_begin_offset(), position.get_end_offset(), ""), lossless);
+        }
+    }
+}
+
+#[test]
+fn edits_inside_tokens_match_full_relex()
+{
+    for &lossless in [false, true].iter()
+    {
+        let document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+        let (_, inside) = get_offsets(&document);
+        for &offset in inside.iter()
+        {
+            let next = (offset + 1..).find(|&next| SOURCE.is_char_boundary(next)).unwrap();
+            for text in INSERTIONS.iter()
+            {
+                let mut document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+                check_edit(&mut document, &TextEdit::new(offset, offset, text), lossless);
+                let mut document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+                check_edit(&mut document, &TextEdit::new(offset, next, text), lossless);
+            }
+        }
+    }
+}
+
+#[test]
+fn edit_sequences_match_full_relex()
+{
+    let mut seed : u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next_random = |bound : usize|
+    {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+    for &lossless in [false, true].iter()
+    {
+        for _ in 0..50
+        {
+            let mut document = LexedDocument::new_with_options(String::from("test"), SOURCE.to_string(), DEFAULT_TAB_WIDTH, lossless);
+            for _ in 0..20
+            {
+                let source = document.get_source();
+                let mut begin = next_random(source.len() + 1);
+                while !source.is_char_boundary(begin)
+                {
+                    begin -= 1;
+                }
+                let mut end = begin + next_random(cmp::min(source.len() - begin, 8) + 1);
+                while !source.is_char_boundary(end)
+                {
+                    end += 1;
+                }
+                let edit = TextEdit::new(begin, end, INSERTIONS[next_random(INSERTIONS.len())]);
+                check_edit(&mut document, &edit, lossless);
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_edit_range_is_rejected()
+{
+    let mut document = LexedDocument::new(String::from("test"), String::from("a \u{0161} b"));
+    assert!(document.apply_edit(&TextEdit::new(3, 2, "")).is_err());
+    assert!(document.apply_edit(&TextEdit::new(0, 100, "")).is_err());
+    assert!(document.apply_edit(&TextEdit::new(3, 3, "x")).is_err());
+    assert_eq!(document.get_source(), "a \u{0161} b");
+}
@@ -0,0 +1,49 @@
+extern crate proteus_compiler;
+
+use std::sync::Arc;
+use std::thread;
+use proteus_compiler::interner::Name;
+
+#[test]
+fn equal_strings_have_equal_names()
+{
+    assert_eq!(Name::new("interner_equal"), Name::new(&String::from("interner_equal")));
+    assert_ne!(Name::new("interner_equal"), Name::new("interner_other"));
+    assert_eq!(&*Name::new("interner_equal").as_str(), "interner_equal");
+}
+
+#[test]
+fn names_are_shared_between_threads()
+{
+    let local = Name::new("interner_local");
+    let (remote, text) = thread::spawn(move || (Name::new("interner_remote"), local.as_str().to_string())).join().unwrap();
+    assert_eq!(text, "interner_local");
+    assert_eq!(&*remote.as_str(), "interner_remote");
+    assert_eq!(remote, Name::new("interner_remote"));
+}
+
+#[test]
+fn concurrent_interning_gives_one_name_per_string()
+{
+    let threads : Vec<_> = (0..8).map(|_| thread::spawn(||
+    {
+        (0..500).map(|index| Name::new(&format!("interner_concurrent_{}", index))).collect::<Vec<Name>>()
+    })).collect();
+    let names : Vec<Vec<Name>> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    for (index, name) in names[0].iter().enumerate()
+    {
+        assert_eq!(&*name.as_str(), format!("interner_concurrent_{}", index));
+        assert!(names.iter().all(|names| names[index] == *name));
+    }
+}
+
+#[test]
+fn name_with_text_refers_to_the_interned_string()
+{
+    let (name, text) = Name::new_with_text("interner_text");
+    let (other, other_text) = Name::new_with_text("interner_text");
+    assert_eq!(name, other);
+    assert_eq!(name, Name::new("interner_text"));
+    assert_eq!(&*text, "interner_text");
+    assert!(Arc::ptr_eq(&text, &other_text));
+}
@@ -0,0 +1,277 @@
+extern crate proteus_compiler;
+
+use proteus_compiler::interner::Name;
+use proteus_compiler::lexanal::LexicalAnalyzer;
+use proteus_compiler::lexanal::symbol::{LiteralValue, Symbol, Token};
+use proteus_compiler::lexanal::trivia::TriviaKind;
+use proteus_compiler::lexanal::dfa::TokenDfa;
+use proteus_compiler::lexanal::token_table::{TokenPattern, TOKEN_TABLE};
+use proteus_compiler::report::Diagnostic;
+
+
+// prebere vse simbole in vrne simbole in zabelezena obvestila
+fn lex_bytes(source : &[u8]) -> (Vec<Symbol>, Vec<Diagnostic>)
+{
+    let lexical_analyser = LexicalAnalyzer::new_from_reader(String::from("test"), source).unwrap();
+    let diagnostics = lexical_analyser.get_diagnostics();
+    let symbols = lexical_analyser.map(|symbol| symbol.unwrap()).collect();
+    (symbols, diagnostics.get_diagnostics())
+}
+
+fn lex(source : &str) -> (Vec<Symbol>, Vec<Diagnostic>)
+{
+    lex_bytes(source.as_bytes())
+}
+
+#[test]
+fn valid_replacement_character_is_accepted()
+{
+    let (symbols, diagnostics) = lex("\"a\u{FFFD}b\" # \u{FFFD}\n#{ \u{FFFD} }#\n## \u{FFFD}\nx");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert_eq!(symbols[0].get_value(), Some(&LiteralValue::Str(String::from("a\u{FFFD}b"))));
+}
+
+#[test]
+fn invalid_utf8_is_reported()
+{
+    let (symbols, diagnostics) = lex_bytes(b"\"a\xFFb\" # \xC3(\nx \xE2\x82 y");
+    let messages : Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, ["Invalid UTF-8 byte sequence in string", "Invalid UTF-8 byte sequence in comment", "Invalid UTF-8 byte sequence"]);
+    assert_eq!(symbols[0].get_value(), Some(&LiteralValue::Str(String::from("ab"))));
+    let tokens : Vec<Token> = symbols.iter().map(|symbol| symbol.get_token()).collect();
+    assert_eq!(tokens, [Token::STRINGCONST, Token::IDENTIFIER, Token::ERROR, Token::IDENTIFIER]);
+}
+
+#[test]
+fn integer_constants_are_limited_to_i64_max()
+{
+    let (symbols, diagnostics) = lex("9223372036854775807 0x7FFF_FFFF_FFFF_FFFF");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert!(symbols.iter().all(|symbol| symbol.get_value() == Some(&LiteralValue::Int(i64::MAX))));
+
+    let (symbols, diagnostics) = lex("-9223372036854775808 9223372036854775809");
+    let messages : Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, ["Integer constant 9223372036854775808 is out of range (at most 9223372036854775807); \
+                           write the smallest integer as -9223372036854775807 - 1",
+                          "Integer constant 9223372036854775809 is out of range (at most 9223372036854775807)"]);
+    assert_eq!(symbols[1].get_token(), Token::ERROR);
+}
+
+// v nacinu brez izgub prebere vse simbole in sestavi besedilo simbolov in delov med njimi
+fn lossless_text(source : &str) -> (String, Vec<TriviaKind>)
+{
+    let mut lexical_analyser = LexicalAnalyzer::new_from_str(String::from("test"), source);
+    lexical_analyser.set_lossless(true);
+    let mut text = String::new();
+    let mut kinds = Vec::new();
+    while let Some(symbol) = lexical_analyser.get_next_symbol().unwrap()
+    {
+        let position = symbol.get_ref_position();
+        assert_eq!(&source[position.get_begin_offset()..position.get_end_offset()], symbol.get_ref_lexeme());
+        for trivia in symbol.get_leading_trivia().iter().chain(symbol.get_trailing_trivia())
+        {
+            let position = trivia.get_position();
+            assert_eq!(&source[position.get_begin_offset()..position.get_end_offset()], trivia.get_text());
+            kinds.push(trivia.get_kind());
+        }
+        text.push_str(&symbol.get_full_text());
+    }
+    for trivia in lexical_analyser.get_end_trivia()
+    {
+        kinds.push(trivia.get_kind());
+        text.push_str(trivia.get_text());
+    }
+    (text, kinds)
+}
+
+#[test]
+fn lossless_mode_reproduces_source()
+{
+    let sources = ["",
+                   "  \t\r\n",
+                   "x",
+                   "## doc\r\nfun\tf(a:int):int = a + 1 # comment\r\n\t# line\r\n",
+                   "a #{ block #{ nested }# \r\n still }# b\r\rc\n\n",
+                   "\"multi\r\nline \\t string\"\t,\t1_000 ,0x1F\t# trailing",
+                   "{ x = 1 }\t\t#{ unterminated\r\n",
+                   "a $ b \u{0161}\u{010D} \"\u{017E}\"\r\n"];
+    for source in sources.iter()
+    {
+        let (text, _) = lossless_text(source);
+        assert_eq!(&text, source);
+    }
+
+    let (_, kinds) = lossless_text("## doc\r\nx\t# comment\r\n#{ b }#y\n");
+    assert_eq!(kinds, [TriviaKind::DocComment, TriviaKind::Newline, TriviaKind::Whitespace, TriviaKind::Comment,
+                       TriviaKind::Newline, TriviaKind::BlockComment, TriviaKind::Newline]);
+}
+
+// rezervirane besede, kot jih je prepoznal rocno napisani leksikalni analizator
+const RESERVED_WORDS : &[(&str, Token)] = &[
+    ("int", Token::INT), ("real", Token::REAL), ("bool", Token::BOOL), ("string", Token::STRING), ("arr", Token::ARR),
+    ("else", Token::ELSE), ("for", Token::FOR), ("fun", Token::FUN), ("if", Token::IF), ("import", Token::IMPORT),
+    ("rec", Token::REC), ("then", Token::THEN), ("typ", Token::TYP), ("var", Token::VAR), ("where", Token::WHERE),
+    ("while", Token::WHILE), ("true", Token::BOOLCONST), ("false", Token::BOOLCONST),
+];
+
+// rezervirane besede, operatorji, locila in imena po pravilih rocno napisanega leksikalnega analizatorja
+fn reference_tokens(source : &str) -> Vec<(Token, String)>
+{
+    let chars : Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len()
+    {
+        let chr = chars[index];
+        let next = chars.get(index + 1).cloned();
+        let (token, length) = match chr
+        {
+            ' ' | '\t' | '\n' | '\r' =>
+            {
+                index += 1;
+                continue;
+            },
+            '+' => (Token::ADD, 1),
+            '-' => (Token::SUB, 1),
+            '*' => (Token::MUL, 1),
+            '/' => (Token::DIV, 1),
+            '%' => (Token::MOD, 1),
+            '!' => (Token::NOT, 1),
+            '&' => (Token::AND, 1),
+            '|' => (Token::OR, 1),
+            '=' if next == Some('=') => (Token::EQU, 2),
+            '=' => (Token::ASSIGN, 1),
+            '<' if next == Some('>') => (Token::NEQ, 2),
+            '<' if next == Some('=') => (Token::LEQ, 2),
+            '<' => (Token::LTH, 1),
+            '>' if next == Some('=') => (Token::GEQ, 2),
+            '>' => (Token::GTH, 1),
+            '(' => (Token::LPARENT, 1),
+            ')' => (Token::RPARENT, 1),
+            '[' => (Token::LBRACKET, 1),
+            ']' => (Token::RBRACKET, 1),
+            '{' => (Token::LBRACE, 1),
+            '}' => (Token::RBRACE, 1),
+            '.' => (Token::DOT, 1),
+            ',' => (Token::COMMA, 1),
+            ':' => (Token::COLON, 1),
+            ';' => (Token::SEMIC, 1),
+            c if c.is_alphabetic() || c == '_' =>
+            {
+                let length = chars[index..].iter().take_while(|c| c.is_alphabetic() || c.is_ascii_digit() || **c == '_').count();
+                let word : String = chars[index..index + length].iter().collect();
+                let token = RESERVED_WORDS.iter().find(|&&(reserved, _)| reserved == word).map_or(Token::IDENTIFIER, |&(_, token)| token);
+                (token, length)
+            },
+            c => panic!("unexpected character {:?} in generated source", c),
+        };
+        tokens.push((token, chars[index..index + length].iter().collect()));
+        index += length;
+    }
+    tokens
+}
+
+#[test]
+fn token_table_matches_hand_written_lexer()
+{
+    let mut fragments : Vec<&str> = RESERVED_WORDS.iter().map(|&(word, _)| word).collect();
+    fragments.extend_from_slice(&["in", "integer", "int_", "_if", "iff", "re", "whil", "wherever", "truefalse", "x1", "a_9", "_",
+                                  "\u{010D}", "\u{017E}ito", "stra\u{00DF}e", "\u{03A9}", "import2",
+                                  "+", "-", "*", "/", "%", "!", "&", "|", "=", "==", "<", ">", "<>", "<=", ">=", "=<", "><",
+                                  "(", ")", "[", "]", "{", "}", ".", ",", ":", ";"]);
+    let separators = ["", "", "", " ", "\t", "\n", "\r\n"];
+    // preprost generator psevdonakljucnih stevil, da je test ponovljiv
+    let mut seed : u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next_random = |bound : usize|
+    {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+    for _ in 0..2000
+    {
+        let mut source = String::new();
+        for _ in 0..next_random(20)
+        {
+            source.push_str(fragments[next_random(fragments.len())]);
+            source.push_str(separators[next_random(separators.len())]);
+        }
+        let (symbols, diagnostics) = lex(&source);
+        assert!(diagnostics.is_empty(), "{:?}: {:?}", source, diagnostics);
+        let tokens : Vec<(Token, String)> = symbols.iter().map(|symbol| (symbol.get_token(), symbol.get_ref_lexeme().to_string())).collect();
+        assert_eq!(tokens, reference_tokens(&source), "{:?}", source);
+    }
+}
+
+#[test]
+fn token_dfa_accepts_every_table_literal()
+{
+    let dfa = TokenDfa::new(TOKEN_TABLE);
+    for &(token, ref pattern) in TOKEN_TABLE
+    {
+        if let TokenPattern::Literal(literal) = *pattern
+        {
+            let mut state = dfa.get_start_state();
+            for chr in literal.chars()
+            {
+                state = dfa.get_next_state(state, chr).unwrap();
+            }
+            assert_eq!(dfa.get_token(state), Some(token), "{}", literal);
+            assert_eq!(dfa.get_lexeme(state).map(|(lexeme, text)| (lexeme.to_string(), text.to_string())), Some((literal.to_string(), literal.to_string())));
+        }
+    }
+}
+
+// vrste, besedila in vrednosti vseh simbolov
+fn describe_symbols(symbols : &[Symbol]) -> Vec<(Token, String, Option<LiteralValue>)>
+{
+    symbols.iter().map(|symbol| (symbol.get_token(), symbol.get_ref_lexeme().to_string(), symbol.get_value().cloned())).collect()
+}
+
+#[test]
+fn constants_are_recognized_from_the_token_table()
+{
+    let (symbols, diagnostics) = lex("1_000 0x_Ff 0o17 0b1_01 1._5e+1_0 2.5E-3 \"a\\\"\\\\\\'\\n\\t\\r\\0\\x41\\u{17E}\tb\r\nc\" true");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert_eq!(describe_symbols(&symbols),
+               [(Token::INTCONST, String::from("1_000"), Some(LiteralValue::Int(1000))),
+                (Token::INTCONST, String::from("0x_Ff"), Some(LiteralValue::Int(255))),
+                (Token::INTCONST, String::from("0o17"), Some(LiteralValue::Int(15))),
+                (Token::INTCONST, String::from("0b1_01"), Some(LiteralValue::Int(5))),
+                (Token::REALCONST, String::from("1._5e+1_0"), Some(LiteralValue::Real(1.5e10))),
+                (Token::REALCONST, String::from("2.5E-3"), Some(LiteralValue::Real(2.5e-3))),
+                (Token::STRINGCONST, String::from("\"a\\\"\\\\\\'\\n\\t\\r\\0\\x41\\u{17E}\tb\r\nc\""),
+                 Some(LiteralValue::Str(String::from("a\"\\'\n\t\r\0A\u{17E}\tb\r\nc")))),
+                (Token::BOOLCONST, String::from("true"), Some(LiteralValue::Bool(true)))]);
+}
+
+#[test]
+fn malformed_constants_are_reported()
+{
+    let (symbols, diagnostics) = lex("0x 12ab 1. 1.5e+ 0b12 \"a\\qb\\u{D800}\" \"open");
+    let messages : Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, ["Incomplete numeric constant 0x",
+                          "Invalid character 'a' in numeric constant 12ab",
+                          "Incomplete numeric constant 1.",
+                          "Incomplete numeric constant 1.5e+",
+                          "Invalid character '2' in numeric constant 0b12",
+                          "Error: String escape \\q!",
+                          "Error: String escape \\u{D800} is not a valid Unicode character!",
+                          "Error: End of file occured, but string not closed!"]);
+    let tokens : Vec<Token> = symbols.iter().map(|symbol| symbol.get_token()).collect();
+    assert_eq!(tokens, [Token::ERROR, Token::ERROR, Token::ERROR, Token::ERROR, Token::ERROR, Token::STRINGCONST, Token::ERROR]);
+    // napacna ubezna zaporedja se izpustijo, ostanek konstante pa ostane
+    assert_eq!(symbols[5].get_value(), Some(&LiteralValue::Str(String::from("ab"))));
+}
+
+#[test]
+fn only_identifiers_and_reserved_words_are_interned()
+{
+    let (symbols, diagnostics) = lex("while x 42 2.5 \"x\" true + x");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    let interned : Vec<bool> = symbols.iter().map(|symbol| symbol.get_name().is_some()).collect();
+    assert_eq!(interned, vec![true, true, false, false, false, false, true, true]);
+    assert_eq!(symbols[1].get_name(), symbols[7].get_name());
+    assert_eq!(symbols[1].get_name(), Some(Name::new("x")));
+}
@@ -0,0 +1,376 @@
+extern crate proteus_compiler;
+
+use std::thread;
+use proteus_compiler::{compile_str, parse_expression_str, parse_type_str, CompileOptions};
+use proteus_compiler::lexanal::LexicalAnalyzer;
+use proteus_compiler::lexanal::symbol::Token;
+use proteus_compiler::report::ExitCode;
+use proteus_compiler::abstree::AbsTree;
+use proteus_compiler::abstree::abs_decl::{AbsDecls, AbsFunDecl, AbsTypeDecl, AbsVarDecl};
+use proteus_compiler::abstree::abs_expr::{AbsAtomExpr, AbsBinExpr, AbsBinOper, AbsExprName, AbsExprs, AbsFunCall, AbsUnExpr, AbsUnOper, AbsWhereExpr};
+use proteus_compiler::abstree::abs_stmt::{AbsAssignStmt, AbsForStmt, AbsIfStmt, AbsWhileStmt};
+use proteus_compiler::abstree::abs_type::{AbsArrType, AbsAtomType, AbsPointerType, AbsRecType, AbsTypeName, AtomType};
+use proteus_compiler::abstree::visitor::Visitor;
+use proteus_compiler::synanal::DEFAULT_MAX_NESTING_DEPTH;
+
+
+// zapise drevo kot izraz v oklepajih, npr. (ADD a (MUL b c))
+struct TreePrinter
+{
+    text : String,
+}
+
+impl TreePrinter
+{
+    fn node(&mut self, name : &str, children : &[&AbsTree])
+    {
+        self.text.push('(');
+        self.text.push_str(name);
+        for child in children
+        {
+            self.text.push(' ');
+            child.accept(self);
+        }
+        self.text.push(')');
+    }
+}
+
+fn print_tree(tree : &AbsTree) -> String
+{
+    let mut printer = TreePrinter { text : String::new() };
+    tree.accept(&mut printer);
+    printer.text
+}
+
+impl Visitor for TreePrinter
+{
+    fn visit_abs_arr_type(&mut self, acceptor : &AbsArrType)
+    {
+        self.node("arr", &[acceptor.size.as_ref(), acceptor.arr_type.as_ref()]);
+    }
+    fn visit_abs_assign_stmt(&mut self, acceptor : &AbsAssignStmt)
+    {
+        self.node("=", &[acceptor.left_sub_expr.as_ref(), acceptor.right_sub_expr.as_ref()]);
+    }
+    fn visit_abs_atom_expr(&mut self, acceptor : &AbsAtomExpr)
+    {
+        let lexeme = acceptor.expr.as_ref().map_or(String::new(), |symbol| symbol.get_ref_lexeme().to_string());
+        self.text.push_str(&lexeme);
+    }
+    fn visit_abs_atom_type(&mut self, acceptor : &AbsAtomType)
+    {
+        self.text.push_str(match acceptor.atom_type
+        {
+            AtomType::INT => "int",
+            AtomType::REAL => "real",
+            AtomType::BOOL => "bool",
+            AtomType::STRING => "string",
+            AtomType::VOID => "void",
+        });
+    }
+    fn visit_abs_bin_expr(&mut self, acceptor : &AbsBinExpr)
+    {
+        let name = match acceptor.operation
+        {
+            AbsBinOper::OR => "OR",
+            AbsBinOper::AND => "AND",
+            AbsBinOper::EQU => "EQU",
+            AbsBinOper::NEQ => "NEQ",
+            AbsBinOper::LTH => "LTH",
+            AbsBinOper::GTH => "GTH",
+            AbsBinOper::LEQ => "LEQ",
+            AbsBinOper::GEQ => "GEQ",
+            AbsBinOper::ADD => "ADD",
+            AbsBinOper::SUB => "SUB",
+            AbsBinOper::MUL => "MUL",
+            AbsBinOper::DIV => "DIV",
+            AbsBinOper::MOD => "MOD",
+            AbsBinOper::ARR => "ARR",
+            AbsBinOper::REC => "REC",
+        };
+        self.node(name, &[acceptor.left_sub_expr.as_ref(), acceptor.right_sub_expr.as_ref()]);
+    }
+    fn visit_abs_decls(&mut self, acceptor : &AbsDecls)
+    {
+        let decls : Vec<&AbsTree> = acceptor.decls.iter().map(|decl| decl.as_ref() as &AbsTree).collect();
+        self.node("decls", &decls);
+    }
+    fn visit_abs_expr_name(&mut self, acceptor : &AbsExprName)
+    {
+        let name = acceptor.identifier.get_ref_lexeme().to_string();
+        self.text.push_str(&name);
+    }
+    fn visit_abs_exprs(&mut self, acceptor : &AbsExprs)
+    {
+        let exprs : Vec<&AbsTree> = acceptor.exprs.iter().map(|expr| expr.as_ref() as &AbsTree).collect();
+        self.node("exprs", &exprs);
+    }
+    fn visit_abs_for_stmt(&mut self, acceptor : &AbsForStmt)
+    {
+        self.node("for", &[&acceptor.var_name, acceptor.lower_bound.as_ref(), acceptor.higher_bound.as_ref(), acceptor.loop_exprs.as_ref()]);
+    }
+    fn visit_abs_fun_call(&mut self, acceptor : &AbsFunCall)
+    {
+        self.node("call", &[&acceptor.name, &acceptor.args]);
+    }
+    fn visit_abs_fun_decl(&mut self, acceptor : &AbsFunDecl)
+    {
+        self.node("fun", &[&acceptor.name, &acceptor.params, acceptor.return_type.as_ref(), acceptor.exprs.as_ref()]);
+    }
+    fn visit_abs_if_stmt(&mut self, acceptor : &AbsIfStmt)
+    {
+        match acceptor.else_expr
+        {
+            Some(ref else_expr) => self.node("if", &[acceptor.cond_expr.as_ref(), acceptor.then_expr.as_ref(), else_expr.as_ref()]),
+            None => self.node("if", &[acceptor.cond_expr.as_ref(), acceptor.then_expr.as_ref()]),
+        }
+    }
+    fn visit_abs_pointer_type(&mut self, acceptor : &AbsPointerType)
+    {
+        self.node("ptr", &[acceptor.ptype.as_ref()]);
+    }
+    fn visit_abs_rec_type(&mut self, acceptor : &AbsRecType)
+    {
+        self.node("rec", &[&acceptor.compoments]);
+    }
+    fn visit_abs_type_decl(&mut self, acceptor : &AbsTypeDecl)
+    {
+        self.node("typ", &[&acceptor.type_name, acceptor.source_type.as_ref()]);
+    }
+    fn visit_abs_type_name(&mut self, acceptor : &AbsTypeName)
+    {
+        let name = acceptor.identifier.get_ref_lexeme().to_string();
+        self.text.push_str(&name);
+    }
+    fn visit_abs_abs_un_expr(&mut self, acceptor : &AbsUnExpr)
+    {
+        let name = match acceptor.operation
+        {
+            AbsUnOper::ADD => "+",
+            AbsUnOper::SUB => "-",
+            AbsUnOper::MUL => "*",
+            AbsUnOper::AND => "&",
+            AbsUnOper::NOT => "!",
+        };
+        self.node(name, &[acceptor.sub_expr.as_ref()]);
+    }
+    fn visit_abs_var_decl(&mut self, acceptor : &AbsVarDecl)
+    {
+        self.node("var", &[&acceptor.var_name, acceptor.var_type.as_ref()]);
+    }
+    fn visit_abs_where_expr(&mut self, acceptor : &AbsWhereExpr)
+    {
+        self.node("where", &[acceptor.sub_expr.as_ref(), &acceptor.decls]);
+    }
+    fn visit_abs_while_stmt(&mut self, acceptor : &AbsWhileStmt)
+    {
+        self.node("while", &[acceptor.cond_expr.as_ref(), acceptor.loop_expr.as_ref()]);
+    }
+}
+
+fn parse_expression(source : &str) -> String
+{
+    match parse_expression_str(source)
+    {
+        Ok(expr) => print_tree(expr.as_ref()),
+        Err(diagnostics) => panic!("{}: {:?}", source, diagnostics.iter().map(|diagnostic| &diagnostic.message).collect::<Vec<_>>()),
+    }
+}
+
+fn get_error_messages(source : &str) -> Vec<String>
+{
+    let result = compile_str("test", source, &CompileOptions::default()).unwrap();
+    result.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).map(|diagnostic| diagnostic.message.clone()).collect()
+}
+
+// razcleni vir v niti z obicajnim 8 MB skladom (glavna nit procesa) in vrne sporocila o napakah
+fn get_error_messages_on_main_stack(source : String) -> Vec<String>
+{
+    thread::Builder::new().stack_size(8 << 20).spawn(move || get_error_messages(&source)).unwrap().join().unwrap()
+}
+
+
+#[test]
+fn syntax_error_exposes_expected_tokens()
+{
+    let result = compile_str("test", "{if a b}", &CompileOptions::default()).unwrap();
+    let errors : Vec<_> = result.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).collect();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].expected.contains(&Token::THEN), "{:?}", errors[0].expected);
+    assert!(errors[0].message.starts_with("expected "), "{}", errors[0].message);
+}
+
+#[test]
+fn unexpected_end_of_file_exposes_expected_tokens()
+{
+    let result = compile_str("test", "f(a,", &CompileOptions::default()).unwrap();
+    let error = result.diagnostics.iter().find(|diagnostic| diagnostic.is_error()).unwrap();
+    assert!(error.position.is_none());
+    assert!(!error.expected.is_empty());
+    assert!(error.expected.contains(&Token::IDENTIFIER), "{:?}", error.expected);
+}
+
+#[test]
+fn binary_operators_associate_to_the_left()
+{
+    assert_eq!(parse_expression("a - b - c"), "(SUB (SUB a b) c)");
+    assert_eq!(parse_expression("a / b * c % d"), "(MOD (MUL (DIV a b) c) d)");
+    assert_eq!(parse_expression("a | b | c"), "(OR (OR a b) c)");
+    assert_eq!(parse_expression("a & b & c"), "(AND (AND a b) c)");
+    assert_eq!(parse_expression("a - (b - c)"), "(SUB a (exprs (SUB b c)))");
+}
+
+#[test]
+fn binary_operators_respect_precedence()
+{
+    assert_eq!(parse_expression("a + b * c"), "(ADD a (MUL b c))");
+    assert_eq!(parse_expression("a * b + c"), "(ADD (MUL a b) c)");
+    assert_eq!(parse_expression("a + b * c - d / e"), "(SUB (ADD a (MUL b c)) (DIV d e))");
+    assert_eq!(parse_expression("a < b + c"), "(LTH a (ADD b c))");
+    assert_eq!(parse_expression("a | b & c == d"), "(OR a (AND b (EQU c d)))");
+    assert_eq!(parse_expression("a == b & c <> d | e"), "(OR (AND (EQU a b) (NEQ c d)) e)");
+    assert_eq!(parse_expression("-a * !b + *c"), "(ADD (MUL (- a) (! b)) (* c))");
+    assert_eq!(parse_expression("a.f + b[i] * c"), "(ADD (REC a f) (MUL (ARR b i) c))");
+}
+
+#[test]
+fn comparison_operators_are_not_associative()
+{
+    for source in ["a < b < c", "a == b == c", "a <= b >= c", "a <> b < c"].iter()
+    {
+        assert!(parse_expression_str(source).is_err(), "{}", source);
+        let messages = get_error_messages(source);
+        assert_eq!(messages.len(), 1, "{}: {:?}", source, messages);
+    }
+    assert_eq!(parse_expression("(a < b) == c"), "(EQU (exprs (LTH a b)) c)");
+    assert_eq!(parse_expression("a < b & c < d"), "(AND (LTH a b) (LTH c d))");
+}
+
+// razcleni vir z omejitvijo gnezdenja v niti s podanim skladom in vrne vsa sporocila
+fn get_messages_with_limit(source : String, max_nesting_depth : usize, stack_size : usize) -> Vec<String>
+{
+    thread::Builder::new().stack_size(stack_size).spawn(move ||
+    {
+        let options = CompileOptions { max_nesting_depth, ..CompileOptions::default() };
+        let result = compile_str("test", &source, &options).unwrap();
+        result.diagnostics.iter().map(|diagnostic| diagnostic.message.clone()).collect::<Vec<String>>()
+    }).unwrap().join().unwrap()
+}
+
+#[test]
+fn long_lists_and_operator_chains_are_not_nested()
+{
+    // elementi seznamov in operandi niso gnezdeni: najglobje je tip komponente zapisa v izrazu where
+    let max_nesting_depth = 3;
+    let count = 100000;
+    let lists = vec![format!("f({})", vec!["a"; count].join(",")),
+                     format!("a where {}", "var x:int;".repeat(count)),
+                     format!("a where var x:rec({});", vec!["x:int"; count].join(",")),
+                     format!("a where fun f({}):int = 1;", vec!["x:int"; count].join(","))];
+    for source in lists
+    {
+        assert_eq!(get_messages_with_limit(source, max_nesting_depth, 8 << 20), Vec::<String>::new());
+    }
+    // drevo verige operatorjev se sprosti rekurzivno, zato potrebuje vecji sklad
+    let count = 20000;
+    let chains = vec![format!("a{}", " + a".repeat(count)),
+                      format!("{}a", "-".repeat(count)),
+                      format!("a{}", "[i]".repeat(count)),
+                      format!("a{}", ".f".repeat(count))];
+    for source in chains
+    {
+        assert_eq!(get_messages_with_limit(source, max_nesting_depth, 64 << 20), Vec::<String>::new());
+    }
+}
+
+#[test]
+fn deep_nesting_is_reported_instead_of_overflowing_the_stack()
+{
+    let depth = 100000;
+    let sources = [format!("{}a{}", "(".repeat(depth), ")".repeat(depth)),
+                   format!("{}a{}", "{if a then ".repeat(depth), "}".repeat(depth)),
+                   format!("{}a{}", "{while a: ".repeat(depth), "}".repeat(depth)),
+                   format!("{}a{}", "{x = ".repeat(depth), "}".repeat(depth)),
+                   format!("{}a{}", "f(".repeat(depth), ")".repeat(depth)),
+                   format!("{{{}x{} = 1}}", "(".repeat(depth), ")".repeat(depth)),
+                   format!("a where var x:{}int;", "*".repeat(depth)),
+                   format!("a where var x:{}int{};", "(".repeat(depth), ")".repeat(depth)),
+                   format!("{}a{}", "(a where fun f(x:int):int = ".repeat(depth), ";)".repeat(depth)),
+                   format!("a where var x:{}int{};", "arr[(a where var y:".repeat(depth), ";)]".repeat(depth))];
+    let limit = format!(" is nested too deeply (the limit is {} levels)", DEFAULT_MAX_NESTING_DEPTH);
+    for source in sources.iter()
+    {
+        let messages = get_error_messages_on_main_stack(source.clone());
+        assert!(messages.iter().any(|message| message.ends_with(&limit)), "{}: {:?}", &source[..40], messages);
+    }
+
+    let deep_type = format!("{}int", "*".repeat(depth));
+    let type_messages = thread::Builder::new().stack_size(8 << 20).spawn(move ||
+    {
+        parse_type_str(&deep_type).err().unwrap().iter().map(|diagnostic| diagnostic.message.clone()).collect::<Vec<String>>()
+    }).unwrap().join().unwrap();
+    assert_eq!(type_messages, [format!("Type is nested too deeply (the limit is {} levels)", DEFAULT_MAX_NESTING_DEPTH)]);
+}
+
+#[test]
+fn expressions_and_types_share_the_nesting_limit()
+{
+    let nested = |depth : usize| format!("a where var x:arr[{}a{}] int;", "(".repeat(depth), ")".repeat(depth));
+    // izraz, tip, izraz v tabeli in oklepaji
+    let options = CompileOptions { max_nesting_depth : 13, ..CompileOptions::default() };
+    assert!(!compile_str("test", &nested(10), &options).unwrap().has_errors());
+    let options = CompileOptions { max_nesting_depth : 12, ..CompileOptions::default() };
+    let result = compile_str("test", &nested(10), &options).unwrap();
+    let messages : Vec<&str> = result.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, ["Expression is nested too deeply (the limit is 12 levels)"]);
+}
+
+#[test]
+fn array_record_and_dereference_lvalues_are_accepted()
+{
+    assert_eq!(parse_expression("{a = 1}"), "(= a 1)");
+    assert_eq!(parse_expression("{a[i] = 1}"), "(= (ARR a i) 1)");
+    assert_eq!(parse_expression("{a[i][j + 1] = x}"), "(= (ARR (ARR a i) (ADD j 1)) x)");
+    assert_eq!(parse_expression("{r.f.g = 1}"), "(= (REC (REC r f) g) 1)");
+    assert_eq!(parse_expression("{a[i].f = b}"), "(= (REC (ARR a i) f) b)");
+    assert_eq!(parse_expression("{*p = 1}"), "(= (* p) 1)");
+    assert_eq!(parse_expression("{**p = 1}"), "(= (* (* p)) 1)");
+    assert_eq!(parse_expression("{*p.f = 1}"), "(= (* (REC p f)) 1)");
+    assert_eq!(parse_expression("{(*p).f[i] = 1}"), "(= (ARR (REC (exprs (* p)) f) i) 1)");
+}
+
+#[test]
+fn lvalues_have_the_same_tree_as_expressions()
+{
+    for lvalue in ["a", "a[i]", "r.f", "*p", "**p.f[i]", "(*p).f", "((a))[i].f", "*(*p)[i]"].iter()
+    {
+        assert_eq!(parse_expression(&format!("{{{} = 0}}", lvalue)), format!("(= {} 0)", parse_expression(lvalue)), "{}", lvalue);
+    }
+}
+
+#[test]
+fn non_lvalues_are_rejected()
+{
+    for source in ["{1 = a}", "{a + b = c}", "{f(x) = 1}", "{-a = 1}", "{&a = 1}", "{!a = 1}", "{(a + b) = 1}",
+                   "{a[i] + 1 = 2}", "{(a, b) = 1}", "{* = 1}", "{\"s\" = 1}", "{(a where var x:int;) = 1}"].iter()
+    {
+        assert!(parse_expression_str(source).is_err(), "{}", source);
+        let messages = get_error_messages(source);
+        assert_eq!(messages.len(), 1, "{}: {:?}", source, messages);
+    }
+    let messages = get_error_messages("{1 = a}");
+    assert!(messages[0].starts_with("expected "), "{:?}", messages);
+}
+
+#[test]
+fn atom_expression_rejects_non_constants()
+{
+    let mut lexical_analyser = LexicalAnalyzer::new_from_str(String::from("test"), "1 x");
+    let constant = lexical_analyser.next().unwrap().unwrap();
+    let identifier = lexical_analyser.next().unwrap().unwrap();
+    assert!(AbsAtomExpr::new(constant).is_ok());
+    let diagnostic = AbsAtomExpr::new(identifier).err().unwrap();
+    assert!(diagnostic.is_error());
+    assert_eq!(diagnostic.exit_code, Some(ExitCode::AbstractSyntaxTreeInvalidExpression));
+    assert_eq!(diagnostic.position.unwrap().get_begin_offset(), 2);
+}
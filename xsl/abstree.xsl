@@ -1,4 +1,4 @@
-<?xml version="1.0" encoding="ISO-8859-1"?>
+<?xml version="1.0" encoding="UTF-8"?>
 <xsl:stylesheet version="1.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
 
 <xsl:template match="abstree">
@@ -13,6 +13,9 @@
 	<table cellspacing="0">
 		<tr>
 			<td align="center" style="background-color:#E69D26">
+				<xsl:if test="@doc">
+					<xsl:attribute name="title"><xsl:value-of select="@doc"/></xsl:attribute>
+				</xsl:if>
 				<xsl:variable name="node" select="@node"/>
 				<xsl:variable name="value" select="@value"/>
 				<text>